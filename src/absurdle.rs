@@ -0,0 +1,100 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::game::{GuessError, Session};
+use crate::matrix::{FeedbackSource, Pattern};
+
+/// Keeps the answer of a session open for as long as possible (Absurdle)
+///
+/// Before each guess the remaining candidates are grouped by the feedback they
+/// would give and only the largest group is kept, so the game commits to an
+/// answer only once a single candidate is left.
+#[derive(Clone, Debug)]
+pub struct Adversary<'a> {
+    candidates: Vec<&'a String>,
+}
+
+impl<'a> Adversary<'a> {
+    /// Returns `None` if there is no candidate at all
+    pub fn new(final_set: &'a [String]) -> Option<Self> {
+        match final_set.is_empty() {
+            true => None,
+            false => Some(Adversary {
+                candidates: final_set.iter().collect(),
+            }),
+        }
+    }
+
+    /// Answers still consistent with every feedback given so far
+    pub fn candidates(&self) -> &[&'a String] {
+        &self.candidates
+    }
+
+    /// The answer the session is currently played against
+    pub fn answer(&self) -> &'a String {
+        self.candidates[0]
+    }
+
+    /// Picks the largest group of candidates for `word` and moves the session
+    /// onto one of them, so that `session.guess(word)` gives that group's feedback
+    ///
+    /// Ties go to the feedback with the fewest green and yellow tiles, then to the
+    /// lowest encoded pattern.
+    pub fn settle(&mut self, session: &mut Session<'a>, word: &str) -> Result<(), GuessError> {
+        let word = session.check(word)?;
+        let alphabet = session.alphabet();
+
+        let mut buckets: HashMap<Pattern, Vec<&'a String>> = HashMap::new();
+        for &answer in &self.candidates {
+            buckets
+                .entry(alphabet.pattern(&word, answer))
+                .or_default()
+                .push(answer);
+        }
+        let (_, largest) = buckets
+            .into_iter()
+            .max_by_key(|&(pattern, ref bucket)| {
+                (
+                    bucket.len(),
+                    Reverse(revealed_tiles(pattern)),
+                    Reverse(pattern),
+                )
+            })
+            .expect("the adversary always has a candidate");
+        self.candidates = largest;
+
+        // 当前答案不在保留的分组中时，换成新答案并重放之前的猜测
+        if !self
+            .candidates
+            .iter()
+            .any(|answer| answer.as_str() == session.answer())
+        {
+            let mut replayed = Session::new(
+                self.answer(),
+                session.rules(),
+                session.acceptable_set(),
+                alphabet,
+            )
+            .expect("the final list only has words of the alphabet");
+            for guess in session.guesses() {
+                replayed
+                    .guess(guess)
+                    .expect("candidates are consistent with every previous guess");
+            }
+            *session = replayed;
+        }
+        Ok(())
+    }
+}
+
+/// Number of green and yellow tiles of a pattern
+fn revealed_tiles(mut pattern: Pattern) -> usize {
+    let mut tiles = 0;
+    while pattern > 0 {
+        if pattern % 3 != 0 {
+            tiles += 1;
+        }
+        pattern /= 3;
+    }
+    tiles
+}
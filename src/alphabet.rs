@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+
+/// The letters words are made of, in keyboard display order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+    indices: HashMap<char, usize>,
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::latin()
+    }
+}
+
+impl Alphabet {
+    /// The built-in alphabet `A` to `Z`
+    pub fn latin() -> Self {
+        Alphabet::new(('A'..='Z').collect()).unwrap()
+    }
+
+    /// Creates an alphabet from its letters, which are upper-cased
+    pub fn new(letters: Vec<char>) -> Result<Self, Box<dyn std::error::Error>> {
+        let letters: Vec<char> = letters.into_iter().map(to_upper).collect();
+        if letters.is_empty() {
+            return Err("The alphabet is empty".into());
+        }
+        let mut indices = HashMap::new();
+        for (i, &letter) in letters.iter().enumerate() {
+            if indices.insert(letter, i).is_some() {
+                return Err(format!("Duplicate letter {} found in the alphabet", letter).into());
+            }
+        }
+        Ok(Alphabet { letters, indices })
+    }
+
+    /// Loads an alphabet by name (`latin`) or from a file listing its letters
+    ///
+    /// Letters in the file are separated by whitespace or written next to each
+    /// other, e.g. `ABCDEFGHIJKLMNÑOPQRSTUVWXYZ`.
+    pub fn load(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if name.eq_ignore_ascii_case("latin") {
+            return Ok(Alphabet::latin());
+        }
+        let content = read_to_string(name)?;
+        Alphabet::new(content.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /// Number of letters
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Letters in display order
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Returns the index of a normalized letter, or `None` if it is not in the alphabet
+    pub fn index(&self, letter: char) -> Option<usize> {
+        self.indices.get(&letter).copied()
+    }
+
+    /// Trims and upper-cases a word letter by letter
+    pub fn normalize(&self, word: &str) -> String {
+        word.trim().chars().map(to_upper).collect()
+    }
+
+    /// Whether every letter of a normalized word is in the alphabet
+    pub fn contains_word(&self, word: &str) -> bool {
+        word.chars().all(|c| self.indices.contains_key(&c))
+    }
+}
+
+/// Upper-cases a letter unless that would turn it into several letters (e.g. `ß`)
+fn to_upper(letter: char) -> char {
+    let mut upper = letter.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(c), None) => c,
+        _ => letter,
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, HashMap};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::alphabet::Alphabet;
+use crate::solver::feedback;
+use crate::state::{Game, State, Summary};
+use crate::status::Status;
+
+/// Output formats of `wordle analyze`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    #[value(name = "table")]
+    Table,
+    #[value(name = "json")]
+    Json,
+}
+
+/// Results of the games opened with one word
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OpenerStats {
+    pub opener: String,
+    pub played: usize,
+    pub wins: usize,
+    /// See [`Summary::average_guesses`]
+    pub average_guesses: f64,
+    /// See [`Summary::win_rate`]
+    pub win_rate: f64,
+}
+
+/// How often the tiles at one position were not green
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PositionStats {
+    /// 1-based position in the word
+    pub position: usize,
+    pub tiles: usize,
+    pub misses: usize,
+    /// Percentage of tiles that were not green
+    pub miss_rate: f64,
+}
+
+/// How often a guessed letter turned out not to be in the answer
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LetterStats {
+    pub letter: char,
+    pub absent: usize,
+}
+
+/// Guesses needed for one answer
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AnswerStats {
+    pub answer: String,
+    pub guesses: usize,
+    pub won: bool,
+}
+
+/// Analytics over the history of a state file
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Analysis {
+    /// Most played openers first
+    pub openers: Vec<OpenerStats>,
+    pub positions: Vec<PositionStats>,
+    /// Letters most often absent from the answer, at most `top`
+    pub absent_letters: Vec<LetterStats>,
+    /// Answers that took the most guesses, failures first, at most `top`
+    pub hardest_answers: Vec<AnswerStats>,
+}
+
+/// Analyses the games of the state, skipping reverse puzzles and words outside the alphabet
+pub fn analyze(state: &State, alphabet: &Alphabet, top: usize) -> Analysis {
+    let games: Vec<&Game> = state
+        .games
+        .iter()
+        .filter(|game| game.targets.is_empty() && fits(game, alphabet))
+        .collect();
+
+    // 按第一个猜测分组
+    let mut openers: BTreeMap<&str, Summary> = BTreeMap::new();
+    for game in &games {
+        if let Some(opener) = game.guesses.first() {
+            openers.entry(opener).or_default().add(game);
+        }
+    }
+    let mut openers: Vec<OpenerStats> = openers
+        .into_iter()
+        .map(|(opener, summary)| OpenerStats {
+            opener: opener.to_string(),
+            played: summary.wins + summary.losses,
+            wins: summary.wins,
+            average_guesses: summary.average_guesses(),
+            win_rate: summary.win_rate(),
+        })
+        .collect();
+    openers.sort_by(|a, b| {
+        b.played
+            .cmp(&a.played)
+            .then_with(|| a.opener.cmp(&b.opener))
+    });
+
+    // 统计每个位置和每个字母的错误
+    let mut positions: Vec<(usize, usize)> = Vec::new();
+    let mut absent: HashMap<char, usize> = HashMap::new();
+    for game in &games {
+        for (answer, played) in game.boards() {
+            for guess in played {
+                let word_state = feedback(guess, answer, alphabet);
+                if positions.len() < word_state.len() {
+                    positions.resize(word_state.len(), (0, 0));
+                }
+                for (i, (letter, status)) in guess.chars().zip(&word_state).enumerate() {
+                    positions[i].0 += 1;
+                    if *status != Status::GREEN {
+                        positions[i].1 += 1;
+                    }
+                    if *status == Status::RED && !answer.contains(letter) {
+                        *absent.entry(letter).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+    let positions = positions
+        .into_iter()
+        .enumerate()
+        .map(|(i, (tiles, misses))| PositionStats {
+            position: i + 1,
+            tiles,
+            misses,
+            miss_rate: match tiles {
+                0 => 0f64,
+                _ => misses as f64 * 100f64 / tiles as f64,
+            },
+        })
+        .collect();
+    let mut absent_letters: Vec<LetterStats> = absent
+        .into_iter()
+        .map(|(letter, absent)| LetterStats { letter, absent })
+        .collect();
+    absent_letters.sort_by(|a, b| {
+        b.absent
+            .cmp(&a.absent)
+            .then_with(|| a.letter.cmp(&b.letter))
+    });
+    absent_letters.truncate(top);
+
+    // 猜测次数最多的答案，失败的排在最前
+    let mut hardest_answers: Vec<AnswerStats> = games
+        .iter()
+        .flat_map(|game| {
+            game.boards()
+                .into_iter()
+                .map(|(answer, played)| AnswerStats {
+                    answer: answer.to_string(),
+                    guesses: played.len(),
+                    won: played.last().is_some_and(|guess| guess == answer),
+                })
+        })
+        .collect();
+    hardest_answers.sort_by(|a, b| {
+        a.won
+            .cmp(&b.won)
+            .then_with(|| b.guesses.cmp(&a.guesses))
+            .then_with(|| a.answer.cmp(&b.answer))
+    });
+    hardest_answers.truncate(top);
+
+    Analysis {
+        openers,
+        positions,
+        absent_letters,
+        hardest_answers,
+    }
+}
+
+/// Whether every word of the game can be scored with the alphabet
+fn fits(game: &Game, alphabet: &Alphabet) -> bool {
+    game.boards().iter().all(|(answer, played)| {
+        alphabet.contains_word(answer)
+            && played.iter().all(|guess| {
+                alphabet.contains_word(guess) && guess.chars().count() == answer.chars().count()
+            })
+    })
+}
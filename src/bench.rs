@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::matrix::{FeedbackSource, Pattern};
+use crate::strategy::Strategy;
+
+/// Results of playing every answer with one strategy
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchReport {
+    pub strategy: String,
+    /// `distribution[i]` is the number of answers solved in `i + 1` guesses
+    pub distribution: Vec<usize>,
+    /// Answers not solved within the allowed guesses
+    pub failures: Vec<String>,
+    /// Answers that took the most guesses among the solved ones
+    pub worst_answers: Vec<String>,
+}
+
+impl BenchReport {
+    pub fn solved(&self) -> usize {
+        self.distribution.iter().sum()
+    }
+
+    /// Average guesses of the solved answers
+    pub fn mean(&self) -> f64 {
+        let total: usize = self
+            .distribution
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (i + 1) * count)
+            .sum();
+        match self.solved() {
+            0 => 0f64,
+            solved => total as f64 / solved as f64,
+        }
+    }
+
+    /// Most guesses needed by a solved answer
+    pub fn worst(&self) -> Option<usize> {
+        self.distribution
+            .iter()
+            .rposition(|&count| count > 0)
+            .map(|i| i + 1)
+    }
+}
+
+/// Plays every word of `final_set` with `strategy` and collects the guess counts
+///
+/// Guesses only depend on the feedback seen so far, so they are computed once
+/// per feedback path and shared between answers. Pass a
+/// [`crate::matrix::FeedbackMatrix`] as `source` for the full word lists.
+pub fn run(
+    strategy: &dyn Strategy,
+    final_set: &[String],
+    acceptable_set: &[String],
+    source: &dyn FeedbackSource,
+    max_guesses: usize,
+) -> Result<BenchReport, Box<dyn std::error::Error>> {
+    let pool: Vec<&String> = acceptable_set.iter().collect();
+    let acceptable: HashSet<&String> = acceptable_set.iter().collect();
+    let all_candidates: Vec<&String> = final_set.iter().collect();
+    let mut chosen: HashMap<Vec<Pattern>, Option<String>> = HashMap::new();
+
+    let mut distribution = vec![0; max_guesses];
+    let mut failures = Vec::new();
+    let mut guesses_used: Vec<(usize, &String)> = Vec::new();
+
+    for answer in final_set {
+        let mut path: Vec<Pattern> = Vec::new();
+        let mut candidates = all_candidates.clone();
+        let mut solved_in = None;
+
+        for turn in 0..max_guesses {
+            let guess = chosen
+                .entry(path.clone())
+                .or_insert_with(|| strategy.choose(turn, &pool, &candidates, source))
+                .clone();
+            let guess = match guess {
+                Some(guess) => guess,
+                None => break,
+            };
+            if !acceptable.contains(&guess) {
+                return Err(format!(
+                    "Strategy {} guessed {}, which is not in the acceptable word list",
+                    strategy.name(),
+                    guess
+                )
+                .into());
+            }
+            if guess == *answer {
+                solved_in = Some(turn + 1);
+                break;
+            }
+            let pattern = source.pattern(&guess, answer);
+            candidates.retain(|candidate| source.pattern(&guess, candidate) == pattern);
+            path.push(pattern);
+        }
+
+        match solved_in {
+            Some(count) => {
+                distribution[count - 1] += 1;
+                guesses_used.push((count, answer));
+            }
+            None => failures.push(answer.to_string()),
+        }
+    }
+
+    let worst = guesses_used.iter().map(|(count, _)| *count).max();
+    let worst_answers = guesses_used
+        .iter()
+        .filter(|(count, _)| Some(*count) == worst)
+        .map(|(_, answer)| answer.to_string())
+        .collect();
+
+    Ok(BenchReport {
+        strategy: strategy.name().to_string(),
+        distribution,
+        failures,
+        worst_answers,
+    })
+}
@@ -1,9 +1,17 @@
-use clap::Parser;
-use serde::Deserialize;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Deserializer};
+use wordle::analyze::ReportFormat;
+use wordle::stats::ExportFormat;
+use wordle::strategy::StrategyKind;
+use wordle::HardMode;
 
 #[derive(Parser, Debug, Deserialize)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+
     #[arg(
         short = 'w',
         long = "word",
@@ -20,15 +28,80 @@ pub struct Cli {
     )]
     pub random: bool,
 
-    #[arg(short = 'D', long = "difficult", default_value_t = false)]
-    pub difficult: bool,
+    /// Adversarial mode: the answer is only fixed once a single candidate is left
+    #[arg(long = "absurdle", conflicts_with_all = ["word", "random", "boards"], default_value_t = false)]
+    #[serde(default)]
+    pub absurdle: bool,
+
+    /// Reverse puzzle: enter words that reproduce each target row against a given answer
+    #[arg(long = "reverse", conflicts_with_all = ["absurdle", "boards"], default_value_t = false)]
+    #[serde(default)]
+    pub reverse: bool,
+
+    /// Fibble: one tile of every feedback row is wrong
+    #[arg(long = "fibble", conflicts_with_all = ["absurdle", "reverse", "boards", "difficult"], default_value_t = false)]
+    #[serde(default)]
+    pub fibble: bool,
+
+    /// Guesses played before the player takes over, e.g. CRANE,SLOTH
+    #[arg(long = "preset-guesses", conflicts_with_all = ["reverse", "fibble"])]
+    pub preset_guesses: Option<String>,
+
+    /// Resume a game of the state file, given by number or `last`, before its final guess
+    #[arg(long = "resume", value_name = "GAME", conflicts_with_all = ["word", "random", "daily", "date", "speedrun", "absurdle", "reverse", "fibble", "preset_guesses"])]
+    pub resume: Option<String>,
+
+    /// Record the time taken by each guess
+    #[arg(long = "timed", default_value_t = false)]
+    #[serde(default)]
+    pub timed: bool,
+
+    /// Seconds allowed for a whole game
+    #[arg(long = "time-limit")]
+    pub time_limit: Option<u64>,
+
+    /// Seconds allowed for each guess
+    #[arg(long = "guess-time-limit")]
+    pub guess_time_limit: Option<u64>,
+
+    /// Play K consecutive seeded puzzles and report the total time
+    #[arg(long = "speedrun", value_name = "K", conflicts_with_all = ["word", "daily", "date"])]
+    pub speedrun: Option<usize>,
+
+    #[arg(
+        short = 'D',
+        long = "difficult",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "nyt"
+    )]
+    #[serde(default, deserialize_with = "deserialize_difficult")]
+    pub difficult: Option<HardMode>,
 
     #[arg(short = 't', long = "stats", default_value_t = false)]
     pub stats: bool,
 
+    #[arg(short = 'H', long = "hint", default_value_t = false)]
+    #[serde(default)]
+    pub hint: bool,
+
     #[arg(short = 'd', long = "day", conflicts_with = "word")]
     pub day: Option<usize>,
 
+    /// Daily mode: the day is counted from the epoch to today's date
+    #[arg(long = "daily", conflicts_with_all = ["word", "day"], default_value_t = false)]
+    #[serde(default)]
+    pub daily: bool,
+
+    /// Play the daily puzzle of another date, YYYY-MM-DD
+    #[arg(long = "date", conflicts_with_all = ["word", "day"])]
+    pub date: Option<String>,
+
+    /// First day of the daily puzzles, YYYY-MM-DD
+    #[arg(long = "epoch", global = true)]
+    pub epoch: Option<String>,
+
     #[arg(short = 's', long = "seed", conflicts_with = "word")]
     pub seed: Option<u64>,
 
@@ -38,44 +111,182 @@ pub struct Cli {
     #[arg(short = 'a', long = "acceptable-set")]
     pub acceptable_set: Option<String>,
 
-    #[arg(short = 'S', long = "state")]
+    #[arg(short = 'l', long = "length")]
+    pub length: Option<usize>,
+
+    #[arg(short = 'm', long = "max-guesses")]
+    pub max_guesses: Option<usize>,
+
+    #[arg(short = 'b', long = "boards")]
+    pub boards: Option<usize>,
+
+    #[arg(short = 'A', long = "alphabet")]
+    pub alphabet: Option<String>,
+
+    #[arg(short = 'C', long = "cache-dir")]
+    pub cache_dir: Option<String>,
+
+    #[arg(short = 'S', long = "state", global = true)]
     pub state: Option<String>,
 
     #[arg(short = 'c', long = "config")]
     pub config: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Play every answer in the final word list with an automatic strategy
+    Bench {
+        #[arg(long = "strategy", value_enum, default_value_t = StrategyKind::Entropy)]
+        strategy: StrategyKind,
+
+        /// First guess of the fixed opener strategy
+        #[arg(long = "opener")]
+        opener: Option<String>,
+    },
+
+    /// List the answers consistent with guesses and their feedback
+    Filter {
+        /// Clues written as GUESS:PATTERN, e.g. CRANE:RYGRR
+        clues: Vec<String>,
+
+        /// Number of wrong tiles in every clue, 1 for Fibble
+        #[arg(long = "lies", default_value_t = 0)]
+        lies: usize,
+    },
+
+    /// Export the games of the state file and their statistics
+    Stats {
+        #[arg(long = "format", value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+
+    /// Analyse the history of the state file by opener, position and answer
+    Analyze {
+        #[arg(long = "format", value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+
+        /// Number of letters and answers listed
+        #[arg(long = "top", default_value_t = 5)]
+        top: usize,
+    },
+
+    /// Show a saved game of the state file again, row by row
+    Replay {
+        /// Number of the game, or last
+        #[arg(long = "game", default_value = "last")]
+        game: String,
+
+        /// Show how many answers remained after each guess
+        #[arg(long = "candidates", default_value_t = false)]
+        candidates: bool,
+    },
+}
+
 impl Cli {
     pub fn merge(&mut self, from: Cli) {
-        if self.word == None {
+        if self.word.is_none() {
             self.word = from.word;
         }
-        if self.random == false {
+        if !self.random {
             self.random = from.random;
         }
-        if self.difficult == false {
+        if !self.absurdle {
+            self.absurdle = from.absurdle;
+        }
+        if !self.reverse {
+            self.reverse = from.reverse;
+        }
+        if !self.fibble {
+            self.fibble = from.fibble;
+        }
+        if self.preset_guesses.is_none() {
+            self.preset_guesses = from.preset_guesses;
+        }
+        if self.resume.is_none() {
+            self.resume = from.resume;
+        }
+        if !self.timed {
+            self.timed = from.timed;
+        }
+        if self.time_limit.is_none() {
+            self.time_limit = from.time_limit;
+        }
+        if self.guess_time_limit.is_none() {
+            self.guess_time_limit = from.guess_time_limit;
+        }
+        if self.speedrun.is_none() {
+            self.speedrun = from.speedrun;
+        }
+        if self.difficult.is_none() {
             self.difficult = from.difficult;
         }
-        if self.stats == false {
+        if !self.stats {
             self.stats = from.stats;
         }
-        if self.day == None {
+        if !self.hint {
+            self.hint = from.hint;
+        }
+        if self.day.is_none() {
             self.day = from.day;
         }
-        if self.seed == None {
+        if !self.daily {
+            self.daily = from.daily;
+        }
+        if self.date.is_none() {
+            self.date = from.date;
+        }
+        if self.epoch.is_none() {
+            self.epoch = from.epoch;
+        }
+        if self.seed.is_none() {
             self.seed = from.seed;
         }
-        if self.final_set == None {
+        if self.final_set.is_none() {
             self.final_set = from.final_set;
         }
-        if self.acceptable_set == None {
+        if self.acceptable_set.is_none() {
             self.acceptable_set = from.acceptable_set;
         }
-        if self.state == None {
+        if self.length.is_none() {
+            self.length = from.length;
+        }
+        if self.max_guesses.is_none() {
+            self.max_guesses = from.max_guesses;
+        }
+        if self.boards.is_none() {
+            self.boards = from.boards;
+        }
+        if self.alphabet.is_none() {
+            self.alphabet = from.alphabet;
+        }
+        if self.cache_dir.is_none() {
+            self.cache_dir = from.cache_dir;
+        }
+        if self.state.is_none() {
             self.state = from.state;
         }
-        if self.config == None {
+        if self.config.is_none() {
             self.config = from.config;
         }
     }
 }
+
+/// Accepts `"difficult": true/false` from older config files besides a level name
+fn deserialize_difficult<'de, D>(deserializer: D) -> Result<Option<HardMode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Difficult {
+        Flag(bool),
+        Level(HardMode),
+    }
+
+    Ok(match Option::<Difficult>::deserialize(deserializer)? {
+        Some(Difficult::Flag(true)) => Some(HardMode::NYT),
+        Some(Difficult::Level(level)) => Some(level),
+        Some(Difficult::Flag(false)) | None => None,
+    })
+}
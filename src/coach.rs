@@ -0,0 +1,155 @@
+use std::cell::OnceCell;
+
+use crate::game::Session;
+use crate::matrix::FeedbackSource;
+use crate::solver::{best_guess, bucket_sizes, remaining_candidates};
+
+/// How one guess compares with the best guess of the reference solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuessRating {
+    pub word: String,
+    /// Answers still possible before the guess
+    pub candidates: usize,
+    /// Answers expected to remain after the guess
+    pub expected: f64,
+    /// Guess leaving the fewest answers on average
+    pub best_word: String,
+    pub best_expected: f64,
+    /// Answers that actually remained after the guess
+    pub remaining: usize,
+}
+
+impl GuessRating {
+    /// 100 for the best guess, lower the more answers it leaves on average
+    pub fn skill(&self) -> f64 {
+        self.best_expected * 100f64 / self.expected
+    }
+
+    /// Answers fewer than expected left by the feedback, negative when unlucky
+    pub fn luck(&self) -> f64 {
+        self.expected - self.remaining as f64
+    }
+}
+
+/// Ratings of every guess of a finished game
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRating {
+    pub guesses: Vec<GuessRating>,
+}
+
+impl GameRating {
+    /// Average skill of the guesses, rounded to two decimals
+    pub fn skill(&self) -> f64 {
+        let total: f64 = self.guesses.iter().map(GuessRating::skill).sum();
+        round(total / self.guesses.len() as f64)
+    }
+
+    /// Total luck of the guesses, rounded to two decimals
+    pub fn luck(&self) -> f64 {
+        round(self.guesses.iter().map(GuessRating::luck).sum())
+    }
+}
+
+/// Answers expected to remain after `guess`, each answer being equally likely
+pub fn expected_remaining(guess: &str, candidates: &[&String], source: &dyn FeedbackSource) -> f64 {
+    let total: usize = bucket_sizes(guess, candidates, source)
+        .into_iter()
+        .map(|count| count * count)
+        .sum();
+    total as f64 / candidates.len() as f64
+}
+
+/// Rates finished games against the reference solver
+///
+/// Every game starts with the whole final list as candidates, so the best first
+/// guess is only searched once for all the games rated by the same coach.
+pub struct Coach<'a> {
+    final_set: &'a [String],
+    opening: OnceCell<Option<(String, f64)>>,
+}
+
+impl<'a> Coach<'a> {
+    pub fn new(final_set: &'a [String]) -> Self {
+        Coach {
+            final_set,
+            opening: OnceCell::new(),
+        }
+    }
+
+    /// Rates each guess of the session
+    ///
+    /// The best guess is searched among the words the session would have accepted
+    /// at that point, so difficult mode is respected. Returns `None` when the game
+    /// has no guess or its clues rule out every answer of the final list.
+    pub fn rate(&self, session: &Session, source: &dyn FeedbackSource) -> Option<GameRating> {
+        let mut replay = Session::new(
+            session.answer(),
+            session.rules(),
+            session.acceptable_set(),
+            session.alphabet(),
+        )
+        .expect("the session was started with the same answer");
+        let mut guesses = Vec::new();
+        for (i, word) in session.guesses().iter().enumerate() {
+            let candidates = remaining_candidates(
+                self.final_set,
+                &session.guesses()[..i],
+                &session.word_states()[..i],
+                session.alphabet(),
+            );
+            if candidates.is_empty() {
+                return None;
+            }
+
+            // 第一步的候选词总是整个词库，只需计算一次
+            let (best_word, best_expected) = match i {
+                0 => self
+                    .opening
+                    .get_or_init(|| best_expected(&replay, &candidates, source))
+                    .clone()?,
+                _ => best_expected(&replay, &candidates, source)?,
+            };
+            let remaining = remaining_candidates(
+                self.final_set,
+                &session.guesses()[..=i],
+                &session.word_states()[..=i],
+                session.alphabet(),
+            )
+            .len();
+            guesses.push(GuessRating {
+                word: word.clone(),
+                candidates: candidates.len(),
+                expected: expected_remaining(word, &candidates, source),
+                best_word,
+                best_expected,
+                remaining,
+            });
+            replay.guess(word).ok()?;
+        }
+        match guesses.is_empty() {
+            true => None,
+            false => Some(GameRating { guesses }),
+        }
+    }
+}
+
+/// Guess the session would accept leaving the fewest answers on average, and that average
+fn best_expected(
+    session: &Session,
+    candidates: &[&String],
+    source: &dyn FeedbackSource,
+) -> Option<(String, f64)> {
+    // 参考求解器在当时可以猜的词中选择
+    let pool = session
+        .acceptable_set()
+        .iter()
+        .filter(|word| session.check(word).is_ok());
+    best_guess(pool, candidates, |word| {
+        -expected_remaining(word, candidates, source)
+    })
+    .map(|(word, score)| (word.clone(), -score))
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100f64).round() / 100f64
+}
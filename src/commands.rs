@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use chrono::NaiveDate;
+use colored::Colorize;
+use wordle::analyze::{self, Analysis, ReportFormat};
+use wordle::bench::{self, BenchReport};
+use wordle::coach::GameRating;
+use wordle::fibble::{Liar, LIES_PER_ROW};
+use wordle::game::update_state;
+use wordle::matrix::FeedbackSource;
+use wordle::solver::{self, Clue};
+use wordle::state::{Game, State, Summary};
+use wordle::stats::{self, ExportFormat, Stats};
+use wordle::status::Status;
+use wordle::strategy::StrategyKind;
+use wordle::Alphabet;
+
+use crate::{colored_letter, print_state_not_tty, print_state_tty};
+
+/// Width of the longest bar in a histogram
+const BAR_WIDTH: usize = 40;
+
+/// Benchmark a strategy over the whole final word list and print the report
+pub fn bench(
+    kind: StrategyKind,
+    opener: Option<&str>,
+    final_set: &[String],
+    acceptable_set: &[String],
+    source: &dyn FeedbackSource,
+    max_guesses: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let strategy = kind.build(opener);
+    let report = bench::run(
+        strategy.as_ref(),
+        final_set,
+        acceptable_set,
+        source,
+        max_guesses,
+    )?;
+    print_bench_report(&report);
+    Ok(())
+}
+
+/// Print the guess count distribution, mean, worst case and failures
+fn print_bench_report(report: &BenchReport) {
+    let total = report.solved() + report.failures.len();
+    println!("Strategy: {} ({} answers)", report.strategy, total);
+
+    print_distribution(&report.distribution, report.failures.len());
+
+    println!("Mean: {:.3} guesses", report.mean());
+    match report.worst() {
+        Some(worst) => println!(
+            "Worst: {} guesses ({})",
+            worst,
+            report.worst_answers.join(", ")
+        ),
+        None => println!("Worst: -"),
+    }
+    match report.failures.len() {
+        0 => println!("Failures: 0"),
+        n => println!("Failures: {} ({})", n, report.failures.join(", ")),
+    }
+}
+
+/// Print a histogram of guess counts with a row for failures
+fn print_distribution(distribution: &[usize], failures: usize) {
+    let rows: Vec<(String, usize)> = distribution
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| ((i + 1).to_string(), count))
+        .chain(std::iter::once(("X".to_string(), failures)))
+        .collect();
+    let highest = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    for (label, count) in rows.iter() {
+        let width = match highest {
+            0 => 0,
+            _ => (count * BAR_WIDTH + highest - 1) / highest,
+        };
+        println!("{:>2} | {:>6} {}", label, count, "#".repeat(width));
+    }
+}
+
+/// Print the statistics panel of the interactive mode
+pub fn print_stats_panel(stats: &Stats, top_words: &[(String, usize)]) {
+    println!("{}", "STATISTICS".bold().blue());
+    println!(
+        "Played: {}  Win %: {:.0}  Current streak: {}  Max streak: {}",
+        stats.played,
+        stats.win_rate(),
+        stats.current_streak,
+        stats.max_streak
+    );
+    println!("Average guesses: {:.2}", stats.average_guesses());
+    if let Some(skill) = stats.skill {
+        println!("Skill: {:.1}", skill);
+    }
+    print_distribution(&stats.distribution, stats.losses());
+    let modes: Vec<String> = stats
+        .modes
+        .iter()
+        .map(|(mode, count)| format!("{} {}", mode, count))
+        .collect();
+    println!("Modes: {}", modes.join(", "));
+    let words: Vec<String> = top_words
+        .iter()
+        .map(|(word, count)| format!("{} {}", word, count))
+        .collect();
+    println!("Top guesses: {}", words.join(", "));
+}
+
+/// Print the results grouped by the number of guesses allowed, if games differ in it
+pub fn print_allowance_summaries(summaries: &BTreeMap<usize, Summary>) {
+    if summaries.len() < 2 {
+        return;
+    }
+    for (max_guesses, summary) in summaries {
+        println!(
+            "{} guesses allowed: {} won, {} lost, {:.2} average, {:.0}% won",
+            max_guesses,
+            summary.wins,
+            summary.losses,
+            summary.average_guesses(),
+            summary.win_rate()
+        );
+    }
+}
+
+/// Print how each guess compares with the reference solver
+pub fn print_rating(rating: &GameRating) {
+    println!("{}", "COACH".bold().blue());
+    for guess in &rating.guesses {
+        println!(
+            "{}  {:>4} left, expected {:.1} (best {} {:.1}), got {}  skill {:.0}  luck {:+.1}",
+            guess.word.bold(),
+            guess.candidates,
+            guess.expected,
+            guess.best_word,
+            guess.best_expected,
+            guess.remaining,
+            guess.skill(),
+            guess.luck()
+        );
+    }
+    println!("Skill: {:.1}  Luck: {:+.1}", rating.skill(), rating.luck());
+}
+
+/// Print the number of answers consistent with the clues, then the answers
+pub fn filter(
+    clues: &[String],
+    lies: usize,
+    final_set: &[String],
+    alphabet: &Alphabet,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let clues = clues
+        .iter()
+        .map(|clue| Clue::parse(clue, alphabet))
+        .collect::<Result<Vec<Clue>, String>>()?;
+    let candidates = solver::filter_candidates(final_set, &clues, alphabet, lies);
+    println!("{}", candidates.len());
+    for word in candidates {
+        println!("{}", word);
+    }
+    Ok(())
+}
+
+/// Replay a game of the state file row by row with the keyboard after each guess
+pub fn replay(
+    state_path: Option<&str>,
+    selector: &str,
+    show_candidates: bool,
+    final_set: &[String],
+    alphabet: &Alphabet,
+    is_tty: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = load_state(state_path)?;
+    let game = state.find_game(selector)?;
+    if !game.targets.is_empty() {
+        return replay_reverse(game, alphabet, is_tty);
+    }
+    // 只有统计候选词时才用到候选词库
+    if let (true, Some(word)) = (show_candidates, final_set.first()) {
+        if word.chars().count() != game.length {
+            return Err(format!("The game was played with {} letters", game.length).into());
+        }
+    }
+
+    // 多面板时每个面板在猜中后结束
+    let boards = game.boards();
+    for &(answer, _) in &boards {
+        if !alphabet.contains_word(answer) {
+            return Err(format!("Answer {} does not fit the alphabet", answer).into());
+        }
+        if answer.chars().count() != game.length {
+            return Err(format!("Answer {} does not have {} letters", answer, game.length).into());
+        }
+    }
+    for &(answer, played) in &boards {
+        match is_tty {
+            true => println!("{} {}", "ANSWER".bold(), answer.bold().green()),
+            false => println!("ANSWER {}", answer),
+        }
+
+        let mut word_states: Vec<Vec<Status>> = Vec::new();
+        let mut alphabet_states: Vec<Vec<Status>> = Vec::new();
+        let mut alphabet_state = vec![Status::UNKNOWN; alphabet.len()];
+        // Fibble 用同一个种子重新说谎，显示玩家当时看到的颜色
+        let mut liar = game.lie_seed.map(|seed| Liar::new(seed, alphabet));
+        for (i, guess) in played.iter().enumerate() {
+            if !alphabet.contains_word(guess) || guess.chars().count() != game.length {
+                return Err(format!("Guess {} does not fit the alphabet", guess).into());
+            }
+            let mut word_state = vec![Status::UNKNOWN; game.length];
+            update_state(
+                guess,
+                answer,
+                &mut word_state,
+                &mut alphabet_state,
+                alphabet,
+            );
+            word_states.push(word_state);
+            alphabet_states.push(alphabet_state.clone());
+            if let Some(liar) = &mut liar {
+                liar.lie(guess, &word_states[i], alphabet);
+            }
+
+            let (rows, keyboards) = match &liar {
+                Some(liar) => (liar.rows(), liar.alphabet_states()),
+                None => (&word_states[..], &alphabet_states[..]),
+            };
+            match is_tty {
+                true => print_state_tty(&played[i..=i], &rows[i..=i], &keyboards[i..=i], alphabet),
+                false => print_state_not_tty(&rows[i], &keyboards[i]),
+            }
+            // 每步之后剩余的候选词
+            if show_candidates {
+                let count = match &liar {
+                    // 猜中的一行不说谎，只剩猜中的词
+                    Some(_) if rows[i].iter().all(|&status| status == Status::GREEN) => {
+                        usize::from(final_set.contains(guess))
+                    }
+                    Some(liar) => {
+                        solver::filter_candidates(final_set, &liar.clues(), alphabet, LIES_PER_ROW)
+                            .len()
+                    }
+                    None => solver::remaining_candidates(
+                        final_set,
+                        &played[..=i],
+                        &word_states,
+                        alphabet,
+                    )
+                    .len(),
+                };
+                match is_tty {
+                    true => println!("{} candidates left", count),
+                    false => println!("CANDIDATES {}", count),
+                }
+            }
+        }
+    }
+
+    match game.is_won() {
+        true => println!("CORRECT {}", game.guesses.len()),
+        false => {
+            let answers: Vec<&str> = boards.iter().map(|&(answer, _)| answer).collect();
+            println!("FAILED {}", answers.join(" "))
+        }
+    }
+    Ok(())
+}
+
+/// Replay a reverse puzzle: its target rows, then whether each word matched the next row
+fn replay_reverse(
+    game: &Game,
+    alphabet: &Alphabet,
+    is_tty: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rows = game
+        .targets
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| Status::from_char(c).ok_or(format!("Target {} is not a pattern", row)))
+                .collect::<Result<Vec<Status>, String>>()
+        })
+        .collect::<Result<Vec<Vec<Status>>, String>>()?;
+    match is_tty {
+        true => {
+            println!("{} {}", "ANSWER".bold(), game.answer.bold().green());
+            for row in &rows {
+                for status in row {
+                    print!("{}", colored_letter('■', *status));
+                }
+                println!();
+            }
+        }
+        false => {
+            println!("PUZZLE {}", game.answer);
+            for row in &game.targets {
+                println!("TARGET {}", row);
+            }
+        }
+    }
+
+    // 与当前目标行相同才算匹配，之后对照下一行
+    let mut matched = 0;
+    for word in &game.guesses {
+        if !alphabet.contains_word(word) || word.chars().count() != game.length {
+            return Err(format!("Guess {} does not fit the alphabet", word).into());
+        }
+        let word_state = solver::feedback(word, &game.answer, alphabet);
+        let is_match = rows.get(matched) == Some(&word_state);
+        if is_match {
+            matched += 1;
+        }
+        match is_tty {
+            true => {
+                for (letter, status) in word.chars().zip(&word_state) {
+                    print!("{}", colored_letter(letter, *status));
+                }
+                match is_match {
+                    true => println!(" {}", "MATCH".bold().green()),
+                    false => println!(" {}", "MISS".bold().red()),
+                }
+            }
+            false => println!(
+                "{} {}",
+                word_state
+                    .iter()
+                    .map(|status| status.to_char())
+                    .collect::<String>(),
+                if is_match { "MATCH" } else { "MISS" }
+            ),
+        }
+    }
+    match is_tty {
+        true => println!("{} of {} rows matched", matched, rows.len()),
+        false => println!("MATCHED {} {}", matched, rows.len()),
+    }
+    Ok(())
+}
+
+/// Print the games of the state file and their statistics as JSON or CSV
+pub fn stats(
+    state_path: Option<&str>,
+    epoch: NaiveDate,
+    format: ExportFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = load_state(state_path)?;
+    print!("{}", stats::export(&state, epoch, format));
+    if format == ExportFormat::Json {
+        println!();
+    }
+    Ok(())
+}
+
+/// Read the state file given with --state
+fn load_state(state_path: Option<&str>) -> Result<State, Box<dyn std::error::Error>> {
+    let state_path = state_path.ok_or("This command needs a state file, pass --state")?;
+    // 只读取不写回，不需要备份
+    Ok(State::from_json(&fs::read(state_path)?)?.0)
+}
+
+/// Print the analytics of the state file as tables or JSON
+pub fn analyze(
+    state_path: Option<&str>,
+    alphabet: &Alphabet,
+    format: ReportFormat,
+    top: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = load_state(state_path)?;
+    let analysis = analyze::analyze(&state, alphabet, top);
+    match format {
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&analysis)?),
+        ReportFormat::Table => print_analysis(&analysis),
+    }
+    Ok(())
+}
+
+/// Print each part of the analysis as a table
+fn print_analysis(analysis: &Analysis) {
+    println!("Opener | Played | Wins | Avg guesses | Win %");
+    for opener in &analysis.openers {
+        println!(
+            "{:<6} | {:>6} | {:>4} | {:>11.2} | {:>5.1}",
+            opener.opener, opener.played, opener.wins, opener.average_guesses, opener.win_rate
+        );
+    }
+    println!();
+    println!("Position | Tiles | Misses | Miss %");
+    for position in &analysis.positions {
+        println!(
+            "{:>8} | {:>5} | {:>6} | {:>6.1}",
+            position.position, position.tiles, position.misses, position.miss_rate
+        );
+    }
+    println!();
+    println!("Letter | Absent");
+    for letter in &analysis.absent_letters {
+        println!("{:<6} | {:>6}", letter.letter, letter.absent);
+    }
+    println!();
+    println!("Answer | Guesses | Won");
+    for answer in &analysis.hardest_answers {
+        println!(
+            "{:<6} | {:>7} | {}",
+            answer.answer,
+            answer.guesses,
+            if answer.won { "yes" } else { "no" }
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use chrono::{Local, NaiveDate};
+
+/// Date of the first daily puzzle unless another epoch is configured
+pub const DEFAULT_EPOCH: &str = "2021-06-19";
+
+/// Parses a date written as `YYYY-MM-DD`
+pub fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Date {} is not written as YYYY-MM-DD", date))
+}
+
+/// Today's date in the local time zone
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Day number of the puzzle of `date`, the epoch being day 1
+pub fn day_index(date: NaiveDate, epoch: NaiveDate) -> Result<usize, String> {
+    let days = (date - epoch).num_days();
+    if days < 0 {
+        return Err(format!("Date {} is before the epoch {}", date, epoch));
+    }
+    Ok(days as usize + 1)
+}
@@ -0,0 +1,87 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::alphabet::Alphabet;
+use crate::game::letter_index;
+use crate::solver::Clue;
+use crate::status::Status;
+
+/// Number of wrong tiles in every row of a Fibble game
+pub const LIES_PER_ROW: usize = 1;
+
+/// Changes exactly one tile of every feedback row before it is shown (Fibble)
+///
+/// The tile and its wrong colour are drawn from the seed, so the same seed
+/// always tells the same lies. Rows of a correct guess are shown as they are.
+#[derive(Clone, Debug)]
+pub struct Liar {
+    rng: StdRng,
+    guesses: Vec<String>,
+    rows: Vec<Vec<Status>>,
+    alphabet_states: Vec<Vec<Status>>,
+    alphabet_state: Vec<Status>,
+}
+
+impl Liar {
+    pub fn new(seed: u64, alphabet: &Alphabet) -> Self {
+        Liar {
+            rng: StdRng::seed_from_u64(seed),
+            guesses: Vec::new(),
+            rows: Vec::new(),
+            alphabet_states: Vec::new(),
+            alphabet_state: vec![Status::UNKNOWN; alphabet.len()],
+        }
+    }
+
+    /// Turns the true feedback of a guess into the row shown to the player
+    pub fn lie(&mut self, guess: &str, word_state: &[Status], alphabet: &Alphabet) -> Vec<Status> {
+        let mut row = word_state.to_vec();
+        if row.iter().any(|&status| status != Status::GREEN) {
+            let position = self.rng.gen_range(0..row.len());
+            let others: Vec<Status> = [Status::RED, Status::YELLOW, Status::GREEN]
+                .into_iter()
+                .filter(|&status| status != row[position])
+                .collect();
+            row[position] = others[self.rng.gen_range(0..others.len())];
+        }
+
+        // 键盘颜色只根据显示出来的颜色更新
+        for (letter, &status) in guess.chars().zip(&row) {
+            let index = letter_index(alphabet, letter);
+            self.alphabet_state[index] = std::cmp::max(self.alphabet_state[index], status);
+        }
+        self.guesses.push(guess.to_string());
+        self.rows.push(row.clone());
+        self.alphabet_states.push(self.alphabet_state.clone());
+        row
+    }
+
+    pub fn guesses(&self) -> &[String] {
+        &self.guesses
+    }
+
+    /// Rows as they were shown, lies included
+    pub fn rows(&self) -> &[Vec<Status>] {
+        &self.rows
+    }
+
+    pub fn alphabet_states(&self) -> &[Vec<Status>] {
+        &self.alphabet_states
+    }
+
+    pub fn alphabet_state(&self) -> &[Status] {
+        &self.alphabet_state
+    }
+
+    /// The shown rows as clues for [`crate::solver::filter_candidates`] with [`LIES_PER_ROW`]
+    pub fn clues(&self) -> Vec<Clue> {
+        self.guesses
+            .iter()
+            .zip(&self.rows)
+            .map(|(guess, row)| Clue {
+                guess: guess.clone(),
+                pattern: row.clone(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+
+use crate::alphabet::Alphabet;
+use crate::hard_mode::{self, HardMode, Violation};
+use crate::status::Status;
+
+pub const DEFAULT_MAX_GUESSES: usize = 6;
+pub const DEFAULT_WORD_LENGTH: usize = 5;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    SUCCESS,
+    FAILED,
+}
+
+/// Rules a round is played under
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// `None` unless playing in difficult mode
+    pub difficult: Option<HardMode>,
+    pub max_guesses: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            difficult: None,
+            max_guesses: DEFAULT_MAX_GUESSES,
+        }
+    }
+}
+
+/// Reasons for a guess to be rejected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuessError {
+    /// The word is not in the acceptable word list
+    NotInWordList,
+    /// The word does not have the same length as the answer
+    WrongLength(usize),
+    /// The word ignores a revealed clue in difficult mode
+    HardModeViolation(Violation),
+    /// The game has already finished
+    GameOver,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::NotInWordList => write!(f, "not in word list"),
+            GuessError::WrongLength(len) => write!(f, "guess must have {} letters", len),
+            GuessError::HardModeViolation(violation) => write!(f, "{}", violation),
+            GuessError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+/// Reasons for an answer to be refused when a round starts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnswerError {
+    /// The normalized answer has letters outside the alphabet
+    OutsideAlphabet(String),
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerError::OutsideAlphabet(answer) => {
+                write!(f, "answer {} has letters outside the alphabet", answer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswerError {}
+
+/// Normalizes an answer, refusing letters that are not in the alphabet
+pub fn check_answer(answer: &str, alphabet: &Alphabet) -> Result<String, AnswerError> {
+    let answer = alphabet.normalize(answer);
+    match alphabet.contains_word(&answer) {
+        true => Ok(answer),
+        false => Err(AnswerError::OutsideAlphabet(answer)),
+    }
+}
+
+/// Feedback of an accepted guess
+#[derive(Clone, Debug)]
+pub struct Feedback {
+    pub word_state: Vec<Status>,
+    pub alphabet_state: Vec<Status>,
+    /// `Some` if this guess finished the game
+    pub outcome: Option<Outcome>,
+}
+
+/// Checks the validity of guessed word
+///
+/// `acceptable_set` must be sorted, it is searched by bisection.
+pub fn is_valid(
+    word: &str,
+    difficult: Option<HardMode>,
+    guesses: &[String],
+    word_states: &[Vec<Status>],
+    acceptable_set: &[String],
+    alphabet: &Alphabet,
+) -> Result<(), GuessError> {
+    if !alphabet.contains_word(word)
+        || acceptable_set
+            .binary_search_by(|w| w.as_str().cmp(word))
+            .is_err()
+    {
+        return Err(GuessError::NotInWordList);
+    }
+    match difficult {
+        Some(mode) => hard_mode::check(word, mode, guesses, word_states, alphabet),
+        None => Ok(()),
+    }
+}
+
+/// Updates the state of the alphabet
+pub fn update_state(
+    guess: &str,
+    answer: &str,
+    word_state: &mut [Status],
+    alphabet_state: &mut [Status],
+    alphabet: &Alphabet,
+) {
+    assert_eq!(alphabet_state.len(), alphabet.len());
+    score_guess(guess, answer, word_state);
+    for (guess_letter, &status) in guess.chars().zip(word_state.iter()) {
+        let index = letter_index(alphabet, guess_letter);
+        alphabet_state[index] = std::cmp::max(alphabet_state[index], status);
+    }
+}
+
+/// Colours the tiles of a guess without allocating, so solvers can score millions of pairs
+pub fn score_guess(guess: &str, answer: &str, word_state: &mut [Status]) {
+    if guess.is_ascii() && answer.is_ascii() {
+        score_letters(guess.bytes(), answer.bytes(), word_state);
+    } else {
+        score_letters(guess.chars(), answer.chars(), word_state);
+    }
+}
+
+fn score_letters<I>(guess: I, answer: I, word_state: &mut [Status])
+where
+    I: Iterator + Clone,
+    I::Item: PartialEq + Copy,
+{
+    assert_eq!(guess.clone().count(), answer.clone().count());
+    assert_eq!(word_state.len(), answer.clone().count());
+
+    let pairs = || guess.clone().zip(answer.clone());
+    // Match all the greens
+    for (i, (guess_letter, std_letter)) in pairs().enumerate() {
+        if guess_letter == std_letter {
+            word_state[i] = Status::GREEN;
+        }
+    }
+    // Match the others
+    for (i, (guess_letter, std_letter)) in pairs().enumerate() {
+        if guess_letter == std_letter {
+            continue;
+        }
+        // 答案中没被绿色占用的同字母个数，够分给此处及之前的非绿色出现就是黄色
+        let mut unmatched = 0;
+        let mut counted = 0;
+        for (j, (g, a)) in pairs().enumerate() {
+            if a == guess_letter && g != a {
+                unmatched += 1;
+            }
+            if j <= i && g == guess_letter && g != a {
+                counted += 1;
+            }
+        }
+        if counted <= unmatched {
+            word_state[i] = std::cmp::max(word_state[i], Status::YELLOW);
+        } else {
+            word_state[i] = std::cmp::max(word_state[i], Status::RED);
+        }
+    }
+}
+
+/// Returns the index of a letter that is known to be in the alphabet
+pub(crate) fn letter_index(alphabet: &Alphabet, letter: char) -> usize {
+    alphabet
+        .index(letter)
+        .unwrap_or_else(|| panic!("Letter {} is not in the alphabet", letter))
+}
+
+/// A single round of Wordle played against a fixed answer
+#[derive(Clone, Debug)]
+pub struct Session<'a> {
+    answer: String,
+    length: usize,
+    rules: Rules,
+    acceptable_set: &'a [String],
+    alphabet: &'a Alphabet,
+    guesses: Vec<String>,
+    word_states: Vec<Vec<Status>>,
+    alphabet_states: Vec<Vec<Status>>,
+    alphabet_state: Vec<Status>,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> Session<'a> {
+    /// Starts a new round; `answer` is upper-cased and fixes the word length
+    pub fn new(
+        answer: &str,
+        rules: Rules,
+        acceptable_set: &'a [String],
+        alphabet: &'a Alphabet,
+    ) -> Result<Self, AnswerError> {
+        let answer = check_answer(answer, alphabet)?;
+        Ok(Session {
+            length: answer.chars().count(),
+            answer,
+            rules,
+            acceptable_set,
+            alphabet,
+            guesses: Vec::new(),
+            word_states: Vec::new(),
+            alphabet_states: Vec::new(),
+            alphabet_state: vec![Status::UNKNOWN; alphabet.len()],
+            outcome: None,
+        })
+    }
+
+    /// Checks whether a word would be accepted as the next guess, returning it normalized
+    pub fn check(&self, word: &str) -> Result<String, GuessError> {
+        if self.outcome.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        let word = self.alphabet.normalize(word);
+        if word.chars().count() != self.length {
+            return Err(GuessError::WrongLength(self.length));
+        }
+        is_valid(
+            &word,
+            self.rules.difficult,
+            &self.guesses,
+            &self.word_states,
+            self.acceptable_set,
+            self.alphabet,
+        )?;
+        Ok(word)
+    }
+
+    /// Submits a guess, returning its feedback or the reason it was rejected
+    pub fn guess(&mut self, word: &str) -> Result<Feedback, GuessError> {
+        let word = self.check(word)?;
+
+        let mut word_state = vec![Status::UNKNOWN; self.length];
+        update_state(
+            &word,
+            &self.answer,
+            &mut word_state,
+            &mut self.alphabet_state,
+            self.alphabet,
+        );
+
+        if word == self.answer {
+            self.outcome = Some(Outcome::SUCCESS);
+        } else if self.guesses.len() + 1 == self.rules.max_guesses {
+            self.outcome = Some(Outcome::FAILED);
+        }
+        self.guesses.push(word);
+        self.word_states.push(word_state.clone());
+        self.alphabet_states.push(self.alphabet_state.clone());
+
+        Ok(Feedback {
+            word_state,
+            alphabet_state: self.alphabet_state.clone(),
+            outcome: self.outcome,
+        })
+    }
+
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn guesses(&self) -> &[String] {
+        &self.guesses
+    }
+
+    pub fn word_states(&self) -> &[Vec<Status>] {
+        &self.word_states
+    }
+
+    pub fn acceptable_set(&self) -> &'a [String] {
+        self.acceptable_set
+    }
+
+    pub fn alphabet(&self) -> &'a Alphabet {
+        self.alphabet
+    }
+
+    pub fn alphabet_states(&self) -> &[Vec<Status>] {
+        &self.alphabet_states
+    }
+
+    pub fn alphabet_state(&self) -> &[Status] {
+        &self.alphabet_state
+    }
+
+    /// `None` while the round is still in progress
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+}
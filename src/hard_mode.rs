@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::alphabet::Alphabet;
+use crate::game::{letter_index, GuessError};
+use crate::status::Status;
+
+/// Levels of difficult mode
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HardMode {
+    /// Greens of the last guess stay in place and its yellows are reused
+    NYT,
+    /// Clues of every previous guess are enforced, including absent letters
+    /// and yellows in the position they were revealed in
+    STRICT,
+    /// Every guess must be a possible answer
+    ULTRA,
+}
+
+/// A clue that a guess in difficult mode fails to respect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// A green letter must stay in its position
+    MustBeAt { position: usize, letter: char },
+    /// A letter is known not to be in this position
+    NotAt { position: usize, letter: char },
+    /// A revealed letter must be used at least `count` times
+    MustContain { letter: char, count: usize },
+    /// A letter is known to be absent from the answer
+    Absent { letter: char },
+    /// A letter appears at most `count` times in the answer
+    AtMost { letter: char, count: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Violation::MustBeAt { position, letter } => {
+                write!(f, "{} letter must be {}", ordinal(position + 1), letter)
+            }
+            Violation::NotAt { position, letter } => {
+                write!(f, "{} letter cannot be {}", ordinal(position + 1), letter)
+            }
+            Violation::MustContain { letter, count } => {
+                write!(f, "guess must contain {} × {}", count, letter)
+            }
+            Violation::Absent { letter } => write!(f, "guess must not contain {}", letter),
+            Violation::AtMost { letter, count } => {
+                write!(f, "guess must contain at most {} × {}", count, letter)
+            }
+        }
+    }
+}
+
+/// Formats 1, 2, 3, 4 as 1st, 2nd, 3rd, 4th
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Checks a guess against the clues revealed so far
+pub fn check(
+    word: &str,
+    mode: HardMode,
+    guesses: &[String],
+    word_states: &[Vec<Status>],
+    alphabet: &Alphabet,
+) -> Result<(), GuessError> {
+    match mode {
+        HardMode::NYT => match (guesses.last(), word_states.last()) {
+            (Some(last_guess), Some(last_word_state)) => {
+                check_last_guess(word, last_guess, last_word_state, alphabet)
+            }
+            _ => Ok(()),
+        },
+        HardMode::STRICT => check_all_guesses(word, guesses, word_states, false),
+        HardMode::ULTRA => check_all_guesses(word, guesses, word_states, true),
+    }
+}
+
+/// Greens of the last guess must stay in place and its yellows must be reused
+fn check_last_guess(
+    word: &str,
+    last_guessed_string: &str,
+    last_word_state: &[Status],
+    alphabet: &Alphabet,
+) -> Result<(), GuessError> {
+    // 标准黄色字母个数
+    let mut std_count = vec![0; alphabet.len()];
+    // 已有黄色字母个数
+    let mut counted = vec![0; alphabet.len()];
+
+    // 检查绿色字母
+    for (i, (letter, std_letter)) in word.chars().zip(last_guessed_string.chars()).enumerate() {
+        if last_word_state[i] == Status::GREEN && letter != std_letter {
+            return Err(GuessError::HardModeViolation(Violation::MustBeAt {
+                position: i,
+                letter: std_letter,
+            }));
+        }
+        if last_word_state[i] == Status::YELLOW {
+            std_count[letter_index(alphabet, std_letter)] += 1;
+        }
+        counted[letter_index(alphabet, letter)] += 1;
+    }
+    // 检查黄色字母
+    for (i, std_letter) in last_guessed_string.chars().enumerate() {
+        let std_index = letter_index(alphabet, std_letter);
+        if last_word_state[i] == Status::YELLOW && counted[std_index] < std_count[std_index] {
+            return Err(GuessError::HardModeViolation(Violation::MustContain {
+                letter: std_letter,
+                count: std_count[std_index],
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Enforces the clues of every previous guess
+///
+/// With `exact`, a red tile also caps the letter at the number of greens and
+/// yellows it has in that row and forbids it in that position, so only words
+/// that could still be the answer pass.
+fn check_all_guesses(
+    word: &str,
+    guesses: &[String],
+    word_states: &[Vec<Status>],
+    exact: bool,
+) -> Result<(), GuessError> {
+    let word: Vec<char> = word.chars().collect();
+    let mut counted: HashMap<char, usize> = HashMap::new();
+    for &letter in word.iter() {
+        *counted.entry(letter).or_insert(0) += 1;
+    }
+
+    for (guess, word_state) in guesses.iter().zip(word_states) {
+        // 本行中绿色和黄色字母个数
+        let mut present: HashMap<char, usize> = HashMap::new();
+        for (position, (letter, status)) in guess.chars().zip(word_state).enumerate() {
+            match status {
+                Status::GREEN if word[position] != letter => {
+                    return Err(GuessError::HardModeViolation(Violation::MustBeAt {
+                        position,
+                        letter,
+                    }))
+                }
+                Status::YELLOW if word[position] == letter => {
+                    return Err(GuessError::HardModeViolation(Violation::NotAt {
+                        position,
+                        letter,
+                    }))
+                }
+                Status::GREEN | Status::YELLOW => *present.entry(letter).or_insert(0) += 1,
+                _ => {}
+            }
+        }
+        // 按字母在猜测中的顺序检查，使提示稳定
+        for letter in guess.chars() {
+            let count = present.get(&letter).copied().unwrap_or(0);
+            if counted.get(&letter).copied().unwrap_or(0) < count {
+                return Err(GuessError::HardModeViolation(Violation::MustContain {
+                    letter,
+                    count,
+                }));
+            }
+        }
+        for (i, (letter, status)) in guess.chars().zip(word_state).enumerate() {
+            if *status != Status::RED {
+                continue;
+            }
+            let count = present.get(&letter).copied().unwrap_or(0);
+            let used = counted.get(&letter).copied().unwrap_or(0);
+            if count == 0 && used > 0 {
+                return Err(GuessError::HardModeViolation(Violation::Absent { letter }));
+            }
+            if exact && used > count {
+                return Err(GuessError::HardModeViolation(Violation::AtMost {
+                    letter,
+                    count,
+                }));
+            }
+            if exact && word[i] == letter {
+                return Err(GuessError::HardModeViolation(Violation::NotAt {
+                    position: i,
+                    letter,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
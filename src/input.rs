@@ -0,0 +1,42 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Lines of standard input read on a background thread, so waiting for one can time out
+pub struct Input {
+    lines: Receiver<io::Result<String>>,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            let stdin = io::stdin();
+            loop {
+                let mut line = String::new();
+                let result = stdin.lock().read_line(&mut line).map(|_| line);
+                // 读到末尾或出错后停止
+                let done = !matches!(&result, Ok(line) if !line.is_empty());
+                if sender.send(result).is_err() || done {
+                    break;
+                }
+            }
+        });
+        Input { lines }
+    }
+
+    /// Next line with its line break, empty at the end of the input like `read_line`
+    pub fn read_line(&self) -> io::Result<String> {
+        self.lines.recv().unwrap_or_else(|_| Ok(String::new()))
+    }
+
+    /// Like [`Input::read_line`], but gives `None` if no line arrives within `timeout`
+    pub fn read_line_timeout(&self, timeout: Duration) -> io::Result<Option<String>> {
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => line.map(Some),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Ok(Some(String::new())),
+        }
+    }
+}
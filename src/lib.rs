@@ -0,0 +1,27 @@
+//! The Wordle game engine, shared by the `wordle` binary and any other frontend
+//! (bots, servers, graders) that needs the game rules.
+
+pub mod absurdle;
+pub mod alphabet;
+pub mod analyze;
+pub mod bench;
+pub mod coach;
+pub mod daily;
+pub mod fibble;
+pub mod game;
+pub mod hard_mode;
+pub mod matrix;
+pub mod multi;
+pub mod reverse;
+pub mod solver;
+pub mod state;
+pub mod stats;
+pub mod status;
+pub mod strategy;
+pub mod timer;
+pub mod words;
+
+pub use alphabet::Alphabet;
+pub use game::{AnswerError, Feedback, GuessError, Outcome, Rules, Session};
+pub use hard_mode::{HardMode, Violation};
+pub use multi::{MultiFeedback, MultiSession};
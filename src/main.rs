@@ -1,191 +1,270 @@
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::Parser;
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
-use std::collections::{HashMap, HashSet};
-use std::fs::{self, read_to_string};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs;
 use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use wordle::absurdle::Adversary;
+use wordle::coach::{Coach, GameRating};
+use wordle::daily;
+use wordle::fibble::{Liar, LIES_PER_ROW};
+use wordle::game::{check_answer, DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH};
+use wordle::matrix::{word_lists_key, FeedbackMatrix, FeedbackSource, MAX_MATRIX_LENGTH};
+use wordle::reverse::{Puzzle, ReverseSession};
+use wordle::solver::{self, Suggestion};
+use wordle::state::{AnswerMode, Game, Metadata, State, StateFile};
+use wordle::stats::Stats;
+use wordle::status::Status;
+use wordle::timer::Timer;
+use wordle::words::{check_subset, default_word_list_path, load_word_list};
+use wordle::{Alphabet, GuessError, MultiFeedback, MultiSession, Outcome, Rules};
 
 mod cli;
-use cli::Cli;
-mod status;
-use status::Status;
-mod state;
-use state::{Game, State};
-
-const TOTAL_CHANCES: usize = 6;
-const WORD_LENGTH: usize = 5;
-const ALPHABET_SIZE: usize = 26;
+use cli::{Cli, Command};
+mod commands;
+mod input;
+use input::Input;
+
 const TOP_N: usize = 5;
+/// Seed of random answers unless --seed is given
+const DEFAULT_SEED: u64 = 19260817;
 
-enum Outcome {
-    SUCCESS,
-    FAILED,
+/// Print the state of the word and the alphabet(not in tty)
+fn print_state_not_tty(word_state: &[Status], alphabet_state: &[Status]) {
+    for status in word_state {
+        print!("{}", status.to_char());
+    }
+    print!(" ");
+    for status in alphabet_state {
+        print!("{}", status.to_char());
+    }
+    println!();
+    io::stdout().flush().unwrap();
 }
 
-/// Checks the validity of guessed word
-fn is_valid(
-    word: &String,
-    difficult: bool,
-    last_guessed_string: &Option<&String>,
-    last_word_state: &Option<&[Status; WORD_LENGTH]>,
-    acceptable_set: &Vec<String>,
-) -> bool {
-    if !difficult || *last_guessed_string == None {
-        acceptable_set.contains(&word.to_string())
-    } else {
-        let last_guessed_string = last_guessed_string.unwrap();
-        let last_word_state = last_word_state.unwrap();
-        let len = word.len();
-        // 标准黄色字母个数
-        let mut std_count = [0; ALPHABET_SIZE];
-        // 已有黄色字母个数
-        let mut counted = [0; ALPHABET_SIZE];
-
-        // 检查绿色字母
-        for i in 0..len {
-            let letter = word.chars().nth(i).unwrap();
-            let std_letter = last_guessed_string.chars().nth(i).unwrap();
-            if last_word_state[i] == Status::GREEN && letter != std_letter {
-                return false;
-            } else {
-                if last_word_state[i] == Status::YELLOW {
-                    let std_index = (std_letter as u8 - b'A') as usize;
-                    std_count[std_index] += 1;
-                }
-                let index = (letter as u8 - b'A') as usize;
-                counted[index] += 1;
-            }
-        }
-        // 检查黄色字母
-        for i in 0..len {
-            let std_letter = last_guessed_string.chars().nth(i).unwrap();
-            let std_index = (std_letter as u8 - b'A') as usize;
-            if last_word_state[i] == Status::YELLOW {
-                if counted[std_index] < std_count[std_index] {
-                    return false;
-                }
-            }
+/// Colour a letter by its status
+fn colored_letter(letter: char, status: Status) -> ColoredString {
+    match status {
+        Status::RED => letter.to_string().bold().red(),
+        Status::YELLOW => letter.to_string().bold().yellow(),
+        Status::GREEN => letter.to_string().bold().green(),
+        Status::UNKNOWN => letter.to_string().bold(),
+    }
+}
+
+/// Print one line per board, SOLVED for boards solved earlier(not in tty)
+fn print_feedback_not_tty(feedback: &MultiFeedback) {
+    for board in &feedback.boards {
+        match board {
+            Some(board) => print_state_not_tty(&board.word_state, &board.alphabet_state),
+            None => println!("SOLVED"),
         }
-        true
     }
 }
 
-/// Updates the state of the alphabet
-fn update_state(
-    guess: &String,
-    answer: &String,
-    word_state: &mut [Status; WORD_LENGTH],
-    alphabet_state: &mut [Status; ALPHABET_SIZE],
+/// Print the state of the word and the alphabet(in tty)
+fn print_state_tty(
+    saved_guessed_strings: &[String],
+    saved_word_state: &[Vec<Status>],
+    saved_alphabet_state: &[Vec<Status>],
+    alphabet: &Alphabet,
 ) {
-    assert_eq!(guess.len(), answer.len());
-    let len = guess.len();
-
-    let mut counted = [0; ALPHABET_SIZE];
-    let std_count: [i32; 26] = answer
-        .chars()
-        .fold([0; 26], |mut acc, c| {
-            let index = (c as u8 - b'A') as usize;
-            acc[index] += 1;
-            acc
-        })
-        .into();
-    // Match all the greens
-    for i in 0usize..len {
-        let guess_letter = guess.chars().nth(i).unwrap();
-        let std_letter = answer.chars().nth(i).unwrap();
-        let index = (guess_letter as u8 - b'A') as usize;
-
-        if guess_letter == std_letter {
-            counted[index] += 1;
-            word_state[i] = Status::GREEN;
-        }
-    }
-    // Match the others
-    for i in 0usize..len {
-        let guess_letter = guess.chars().nth(i).unwrap();
-        let std_letter = answer.chars().nth(i).unwrap();
-        let index = (guess_letter as u8 - b'A') as usize;
-
-        if guess_letter != std_letter {
-            counted[index] += 1;
-
-            if counted[index] <= std_count[index] {
-                word_state[i] = std::cmp::max(word_state[i], Status::YELLOW);
-            } else {
-                word_state[i] = std::cmp::max(word_state[i], Status::RED);
-            }
+    assert_eq!(saved_word_state.len(), saved_alphabet_state.len());
+    for ((guess, word_state), alphabet_state) in saved_guessed_strings
+        .iter()
+        .zip(saved_word_state)
+        .zip(saved_alphabet_state)
+    {
+        for (letter, status) in guess.chars().zip(word_state) {
+            print!("{}", colored_letter(letter, *status));
+        }
+        print!(" ");
+        for (letter, status) in alphabet.letters().iter().zip(alphabet_state) {
+            print!("{}", colored_letter(*letter, *status));
         }
-        alphabet_state[index] = std::cmp::max(alphabet_state[index], word_state[i]);
+        println!();
     }
+    io::stdout().flush().unwrap();
 }
 
-/// Print the state of the word and the alphabet(not in tty)
-fn print_state_not_tty(
-    word_state: &[Status; WORD_LENGTH],
-    alphabet_state: &[Status; ALPHABET_SIZE],
-) {
-    for i in 0..word_state.len() {
-        match word_state[i] {
-            Status::RED => print!("R"),
-            Status::YELLOW => print!("Y"),
-            Status::GREEN => print!("G"),
-            Status::UNKNOWN => print!("X"),
+/// Print every board side by side, then the alphabet of each board(in tty)
+fn print_boards_tty(session: &MultiSession, alphabet: &Alphabet) {
+    let boards = session.boards();
+    if let [board] = boards {
+        print_state_tty(
+            board.guesses(),
+            board.word_states(),
+            board.alphabet_states(),
+            alphabet,
+        );
+        return;
+    }
+    for row in 0..session.guesses().len() {
+        for (i, board) in boards.iter().enumerate() {
+            if i > 0 {
+                print!("  ");
+            }
+            // 已猜出的面板之后留空
+            match (board.guesses().get(row), board.word_states().get(row)) {
+                (Some(guess), Some(word_state)) => {
+                    for (letter, status) in guess.chars().zip(word_state) {
+                        print!("{}", colored_letter(letter, *status));
+                    }
+                }
+                _ => print!("{}", " ".repeat(board.length())),
+            }
         }
+        println!();
     }
-    print!(" ");
-    for letter in 'A'..='Z' {
-        let index = (letter as u8 - b'A') as usize;
-        match alphabet_state[index] {
-            Status::RED => print!("R"),
-            Status::YELLOW => print!("Y"),
-            Status::GREEN => print!("G"),
-            Status::UNKNOWN => print!("X"),
+    for (i, board) in boards.iter().enumerate() {
+        print!("{} ", i + 1);
+        for (letter, status) in alphabet.letters().iter().zip(board.alphabet_state()) {
+            print!("{}", colored_letter(*letter, *status));
         }
+        println!();
     }
-    println!("");
     io::stdout().flush().unwrap();
 }
 
-/// Print the state of the word and the alphabet(in tty)
-fn print_state_tty(
-    saved_guessed_strings: &Vec<String>,
-    saved_word_state: &Vec<[Status; WORD_LENGTH]>,
-    saved_alphabet_state: &Vec<[Status; ALPHABET_SIZE]>,
-) {
-    assert_eq!(saved_word_state.len(), saved_alphabet_state.len());
-    let len = saved_word_state.len();
-    for i in 0..len {
-        let guess = saved_guessed_strings[i].to_string();
-        let word_state = saved_word_state[i];
-        for j in 0..word_state.len() {
-            let letter = guess.chars().nth(j).unwrap();
-            match word_state[j] {
-                Status::RED => print!("{}", letter.to_string().bold().red()),
-                Status::YELLOW => print!("{}", letter.to_string().bold().yellow()),
-                Status::GREEN => print!("{}", letter.to_string().bold().green()),
-                Status::UNKNOWN => print!("{}", letter.to_string().bold()),
+/// Play a reverse puzzle until every row is reproduced or too many words missed
+fn play_reverse(
+    session: &mut ReverseSession,
+    input: &Input,
+    alphabet: &Alphabet,
+    is_tty: bool,
+) -> io::Result<()> {
+    let pattern = |row: &[Status]| {
+        row.iter()
+            .map(|status| status.to_char())
+            .collect::<String>()
+    };
+    let puzzle = session.puzzle().clone();
+    match is_tty {
+        true => {
+            println!("{} {}", "ANSWER".bold(), puzzle.answer.bold().green());
+            for row in &puzzle.rows {
+                for status in row {
+                    print!("{}", colored_letter('■', *status));
+                }
+                println!();
             }
         }
-        print!(" ");
-        let alphabet_state = saved_alphabet_state[i];
-        for letter in 'A'..='Z' {
-            let index = (letter as u8 - b'A') as usize;
-            match alphabet_state[index] {
-                Status::RED => print!("{}", letter.to_string().bold().red()),
-                Status::YELLOW => print!("{}", letter.to_string().bold().yellow()),
-                Status::GREEN => print!("{}", letter.to_string().bold().green()),
-                Status::UNKNOWN => print!("{}", letter.to_string().bold()),
+        false => {
+            println!("PUZZLE {}", puzzle.answer);
+            for row in &puzzle.rows {
+                println!("TARGET {}", pattern(row));
             }
         }
-        println!("");
+    }
+    io::stdout().flush()?;
+
+    while session.outcome().is_none() {
+        let word = input.read_line()?;
+        if word.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        match session.guess(&word) {
+            Ok(feedback) => match is_tty {
+                true => {
+                    let word = alphabet.normalize(&word);
+                    for (letter, status) in word.chars().zip(&feedback.word_state) {
+                        print!("{}", colored_letter(letter, *status));
+                    }
+                    match feedback.matched {
+                        true => println!(" {}", "MATCH".bold().green()),
+                        false => println!(
+                            " {} ({} misses left)",
+                            "MISS".bold().red(),
+                            session.max_misses() - session.misses()
+                        ),
+                    }
+                }
+                false => println!(
+                    "{} {}",
+                    pattern(&feedback.word_state),
+                    if feedback.matched { "MATCH" } else { "MISS" }
+                ),
+            },
+            Err(error) => match is_tty {
+                true => println!("{} {}", "INVALID".bold().red(), error),
+                false => println!("INVALID"),
+            },
+        }
+        io::stdout().flush()?;
+    }
+    Ok(())
+}
+
+/// Print a suggested next guess
+fn print_hint(suggestion: Option<Suggestion>, is_tty: bool) {
+    match (suggestion, is_tty) {
+        (Some(s), true) => println!(
+            "{} {} ({:.2} bits, {} candidates left)",
+            "HINT".bold().blue(),
+            s.word.bold(),
+            s.entropy,
+            s.candidates
+        ),
+        (Some(s), false) => println!("HINT {}", s.word),
+        (None, true) => println!("{} no candidates left", "HINT".bold().blue()),
+        (None, false) => println!("HINT"),
     }
     io::stdout().flush().unwrap();
 }
 
+/// Load the cached feedback matrix if a cache directory is configured
+fn load_matrix(
+    cache_dir: Option<&str>,
+    acceptable_set: &[String],
+    final_set: &[String],
+    alphabet: &Alphabet,
+    length: usize,
+) -> Option<FeedbackMatrix> {
+    let cache_dir = cache_dir.filter(|_| length <= MAX_MATRIX_LENGTH)?;
+    match FeedbackMatrix::load_or_build(Path::new(cache_dir), acceptable_set, final_set, alphabet) {
+        Ok(matrix) => Some(matrix),
+        Err(error) => {
+            eprintln!("Failed to load the feedback matrix: {}", error);
+            None
+        }
+    }
+}
+
+/// Submit a guess, letting the adversary of Absurdle mode pick its answer first
+fn submit<'a>(
+    session: &mut MultiSession<'a>,
+    adversary: &mut Option<Adversary<'a>>,
+    guess: &str,
+) -> Result<MultiFeedback, GuessError> {
+    match adversary {
+        Some(adversary) => {
+            adversary.settle(&mut session.boards_mut()[0], guess)?;
+            session.guess(guess)
+        }
+        None => session.guess(guess),
+    }
+}
+
+/// Formats a time as saved in the metadata of a game
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Seed of the puzzles generated for a game: reverse rows and Fibble lies
+fn puzzle_seed(config: &Cli, bias: usize) -> u64 {
+    let day = config.day.unwrap_or(1).wrapping_add(bias);
+    config.seed.unwrap_or(DEFAULT_SEED).wrapping_add(day as u64)
+}
+
 /// Returns the top n frequent strings
-fn find_most_frequent_strings(strings: &Vec<String>, n: usize) -> Vec<(String, usize)> {
+fn find_most_frequent_strings(strings: &[String], n: usize) -> Vec<(String, usize)> {
     let mut frequency_map: HashMap<String, usize> = HashMap::new();
 
     // 统计出现次数
@@ -195,47 +274,19 @@ fn find_most_frequent_strings(strings: &Vec<String>, n: usize) -> Vec<(String, u
 
     // 排序并返回前 n 个出现次数最多的 String
     let mut frequency_vec: Vec<(String, usize)> = frequency_map.into_iter().collect();
-    frequency_vec.sort_by(|(s1, c1), (s2, c2)| c2.cmp(&c1).then_with(|| s1.cmp(&s2)));
-
-    if frequency_vec.len() < n {
-        frequency_vec
-    } else {
-        frequency_vec.into_iter().take(n).collect()
-    }
-}
-
-/// Load word lists from files
-fn load_word_list(file_path: &String) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let content = read_to_string(file_path)?;
-    let word_list: Vec<String> = content
-        .lines()
-        .map(|line| line.trim().to_uppercase())
-        .collect();
-    let unique_words: HashSet<String> = word_list.iter().cloned().collect();
-
-    if unique_words.len() != word_list.len() {
-        return Err("Duplicate words found in the word list".into());
-    }
-
-    Ok(word_list)
-}
+    frequency_vec.sort_by(|(s1, c1), (s2, c2)| c2.cmp(c1).then_with(|| s1.cmp(s2)));
 
-/// Check subset
-fn check_subset(subset: &[String], superset: &[String]) -> bool {
-    let subset_set: HashSet<&String> = subset.iter().collect();
-    let superset_set: HashSet<&String> = superset.iter().collect();
-    subset_set.is_subset(&superset_set)
+    frequency_vec.into_iter().take(n).collect()
 }
 
 /// The main function for the Wordle game, implement your own logic here
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // let is_tty = atty::is(atty::Stream::Stdout);
-    let is_tty = false;
+    let is_tty = atty::is(atty::Stream::Stdout);
     let mut config = Cli::parse();
 
     // 如果指定了 config.json
-    if config.config != None {
-        if let Ok(file) = fs::read(config.config.clone().unwrap()) {
+    if let Some(config_path) = config.config.clone() {
+        if let Ok(file) = fs::read(config_path) {
             // 如果当前文件存在，则取出来
             let config_file: Cli = serde_json::from_slice(&file).expect("JSON 反序列化失败");
             config.merge(config_file);
@@ -248,12 +299,76 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut win_guesses = 0;
     let mut all_guesses_strings: Vec<String> = Vec::new();
 
-    let mut final_word_list =
-        load_word_list(&config.final_set.unwrap_or("./final_set.txt".to_string()))?;
+    let alphabet = match &config.alphabet {
+        Some(name) => Alphabet::load(name)?,
+        None => Alphabet::latin(),
+    };
+    // 只读取存档的子命令不需要词库
+    match config.command.take() {
+        Some(Command::Stats { format }) => {
+            return commands::stats(
+                config.state.as_deref(),
+                daily::parse_date(config.epoch.as_deref().unwrap_or(daily::DEFAULT_EPOCH))?,
+                format,
+            )
+        }
+        Some(Command::Analyze { format, top }) => {
+            return commands::analyze(config.state.as_deref(), &alphabet, format, top)
+        }
+        command => config.command = command,
+    }
+
+    // 如果指定了 state.json，整局游戏期间锁定，旧版本先升级；其余子命令不锁定存档
+    let mut state_file = None;
+    let mut data = State::default();
+    if let (Some(state_path), None) = (&config.state, &config.command) {
+        let (file, state) = StateFile::open(Path::new(state_path))?;
+        state_file = Some(file);
+        data = state;
+    }
+
+    // 预先填入的猜测；从存档中的某局继续时，最后一步留给玩家
+    let mut preset_guesses: Vec<String> = match &config.preset_guesses {
+        Some(guesses) => guesses.split(',').map(|guess| guess.to_string()).collect(),
+        None => Vec::new(),
+    };
+    if let (Some(selector), None) = (config.resume.clone(), &config.command) {
+        let game = data.find_game(&selector)?;
+        if !game.targets.is_empty() {
+            return Err("Reverse puzzles cannot be resumed".into());
+        }
+        if game.lie_seed.is_some() {
+            return Err("Fibble games cannot be resumed".into());
+        }
+        let answers = match game.answers.is_empty() {
+            true => vec![game.answer.clone()],
+            false => game.answers.clone(),
+        };
+        preset_guesses = game.guesses[..game.guesses.len().saturating_sub(1)].to_vec();
+        config.boards = Some(answers.len());
+        config.word = Some(answers.join(","));
+        // 沿用那局的词长和猜测次数；多词局保存的次数已含每多一个词加的一次
+        config.length = Some(game.length);
+        config.max_guesses = Some(game.max_guesses.saturating_sub(answers.len() - 1));
+    }
+
+    let length = config.length.unwrap_or(DEFAULT_WORD_LENGTH);
+
+    let mut final_word_list = load_word_list(
+        &config
+            .final_set
+            .clone()
+            .unwrap_or(default_word_list_path("final_set", length)),
+        length,
+        &alphabet,
+    )?;
     let mut acceptable_word_list = load_word_list(
         &config
             .acceptable_set
-            .unwrap_or("./acceptable_set.txt".to_string()),
+            .clone()
+            .unwrap_or(default_word_list_path("acceptable_set", length)),
+        length,
+        &alphabet,
     )?;
     if !check_subset(&final_word_list, &acceptable_word_list) {
         return Err(
@@ -265,155 +380,512 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     final_word_list.sort();
     acceptable_word_list.sort();
 
-    let mut data: State = State::default();
+    let rules = Rules {
+        difficult: config.difficult,
+        max_guesses: config.max_guesses.unwrap_or(DEFAULT_MAX_GUESSES),
+    };
+    if rules.max_guesses == 0 {
+        return Err("The number of guesses must be positive".into());
+    }
+    if config.day == Some(0) {
+        return Err("Days are counted from 1".into());
+    }
 
-    // 如果指定了 state.json
-    if config.state != None {
-        if let Ok(file) = fs::read(config.state.clone().unwrap()) {
-            // 如果当前文件存在，则取出来
-            data = serde_json::from_slice(&file).expect("JSON 反序列化失败");
-            for game in data.games.iter() {
-                let answer = &game.answer;
-                let last_guess = game.guesses.last().unwrap();
-                if last_guess == answer {
-                    win_rounds += 1;
-                    win_guesses += game.guesses.len();
-                }
-                total_rounds += 1;
-                game.guesses
-                    .iter()
-                    .for_each(|g| all_guesses_strings.push(g.to_string()));
+    // 每日模式，由日期推算天数，之后按随机模式取答案
+    let mut daily_day = None;
+    let mut daily_date = None;
+    if config.daily || config.date.is_some() {
+        if config.word.is_some() {
+            return Err("Daily mode cannot be combined with a given word".into());
+        }
+        let date = match &config.date {
+            Some(date) => daily::parse_date(date)?,
+            None => daily::today(),
+        };
+        let epoch = daily::parse_date(config.epoch.as_deref().unwrap_or(daily::DEFAULT_EPOCH))?;
+        let day = daily::day_index(date, epoch)?;
+        config.random = true;
+        config.day = Some(day);
+        daily_day = Some(day);
+        daily_date = Some(date.format("%Y-%m-%d").to_string());
+    }
+
+    // 竞速模式，连续进行 K 局随机谜题
+    if let Some(speedrun) = config.speedrun {
+        if speedrun == 0 {
+            return Err("A speedrun needs at least one puzzle".into());
+        }
+        if config.word.is_some() || daily_day.is_some() {
+            return Err("A speedrun plays random puzzles".into());
+        }
+        config.random = true;
+    }
+    let timed = config.timed
+        || config.time_limit.is_some()
+        || config.guess_time_limit.is_some()
+        || config.speedrun.is_some();
+
+    // 求解器使用的反馈矩阵，首次使用时才加载
+    let matrix: OnceCell<Option<FeedbackMatrix>> = OnceCell::new();
+    let source = || -> &dyn FeedbackSource {
+        let matrix = matrix.get_or_init(|| {
+            load_matrix(
+                config.cache_dir.as_deref(),
+                &acceptable_word_list,
+                &final_word_list,
+                &alphabet,
+                length,
+            )
+        });
+        match matrix {
+            Some(matrix) => matrix,
+            None => &alphabet,
+        }
+    };
+    // 开局提示和评分的首步在各局之间相同，只计算一次
+    let opening: OnceCell<Option<Suggestion>> = OnceCell::new();
+    let coach = Coach::new(&final_word_list);
+
+    // 子命令
+    if let Some(command) = config.command.take() {
+        return match command {
+            Command::Bench { strategy, opener } => commands::bench(
+                strategy,
+                opener.as_deref(),
+                &final_word_list,
+                &acceptable_word_list,
+                source(),
+                rules.max_guesses,
+            ),
+            Command::Stats { .. } | Command::Analyze { .. } => {
+                unreachable!("state-only commands are dispatched before the word lists")
             }
+            Command::Replay { game, candidates } => commands::replay(
+                config.state.as_deref(),
+                &game,
+                candidates,
+                &final_word_list,
+                &alphabet,
+                is_tty,
+            ),
+            Command::Filter { clues, lies } => {
+                commands::filter(&clues, lies, &final_word_list, &alphabet)
+            }
+        };
+    }
+
+    for game in data.games.iter() {
+        if game.is_won() {
+            win_rounds += 1;
+            win_guesses += game.guesses.len();
+        }
+        total_rounds += 1;
+        game.guesses
+            .iter()
+            .for_each(|g| all_guesses_strings.push(g.to_string()));
+    }
+
+    // 同一存档不能重玩已完成的每日谜题
+    if let Some(day) = daily_day {
+        if data.games.iter().any(|game| game.day == Some(day)) {
+            return Err(format!("The puzzle of day {} has already been played", day).into());
         }
     }
 
+    if !preset_guesses.is_empty() && (config.reverse || config.fibble) {
+        return Err("Preset guesses cannot be used in reverse or Fibble mode".into());
+    }
+
+    let boards = config.boards.unwrap_or(1);
+    if boards == 0 {
+        return Err("The number of boards must be positive".into());
+    }
+
+    if (config.absurdle || config.reverse) && boards > 1 {
+        return Err("Absurdle and reverse modes are played on a single board".into());
+    }
+    // 反向模式至少要有一行目标颜色
+    if config.reverse && rules.max_guesses < 2 {
+        return Err("Reverse mode needs at least 2 guesses".into());
+    }
+    if config.absurdle && config.reverse {
+        return Err("Absurdle mode cannot be combined with reverse mode".into());
+    }
+    if config.fibble && (config.absurdle || config.reverse || boards > 1) {
+        return Err("Fibble is played on a single board with a fixed answer".into());
+    }
+    if config.fibble && config.difficult.is_some() {
+        return Err("Difficult mode cannot be combined with Fibble".into());
+    }
+
+    let speedrun_started = Instant::now();
+    let mut speedrun_solved = 0;
+    let input = Input::new();
+
     loop {
-        let mut saved_guessed_strings: Vec<String> = Vec::new();
-        let mut saved_alphabet_state: Vec<[Status; ALPHABET_SIZE]> = Vec::new();
-        let mut saved_word_state: Vec<[Status; WORD_LENGTH]> = Vec::new();
-        let mut answer = String::new();
-
-        if config.random {
-            // 如果为随机模式
-            let mut rng = StdRng::seed_from_u64(config.seed.unwrap_or(19260817));
+        let started_at = Utc::now();
+        let mut answers: Vec<String> = Vec::new();
+        let mut adversary = None;
+
+        if config.absurdle {
+            // 对抗模式，答案随猜测变化，先取第一个候选词
+            let absurdle =
+                Adversary::new(&final_word_list).ok_or("The final word list is empty")?;
+            answers.push(absurdle.answer().to_string());
+            adversary = Some(absurdle);
+        } else if config.random {
+            // 如果为随机模式，每天连续取出 boards 个答案，超出词库长度时循环
+            if final_word_list.is_empty() {
+                return Err("The final word list is empty".into());
+            }
+            let mut rng = StdRng::seed_from_u64(config.seed.unwrap_or(DEFAULT_SEED));
             let mut final_set_vec = final_word_list.clone();
             final_set_vec.shuffle(&mut rng);
-            // Get a random string as the final answer
-            answer =
-                final_set_vec[(config.day.unwrap_or(1) + bias - 1) as usize].to_ascii_uppercase();
+            // 先对词库长度取模，避免天数很大时溢出
+            let len = final_set_vec.len();
+            let round = ((config.day.unwrap_or(1) - 1) % len + bias % len) % len;
+            let first = round * (boards % len) % len;
+            answers = (first..first + boards)
+                .map(|i| final_set_vec[i % len].clone())
+                .collect();
+        } else if let Some(word) = &config.word {
+            // 如果指定单词，多个答案用逗号分隔
+            answers = word.split(',').map(|w| w.to_string()).collect();
         } else {
-            if config.word != None {
-                // 如果指定单词
-                answer = config.word.clone().unwrap().to_ascii_uppercase();
-            } else {
-                // 从标准输入取出单词
-                io::stdin().read_line(&mut answer)?;
+            // 从标准输入取出单词，直到凑够 boards 个
+            while answers.len() < boards {
+                let line = input.read_line()?;
+                if line.is_empty() {
+                    break;
+                }
+                if boards == 1 {
+                    answers.push(line);
+                } else {
+                    answers.extend(
+                        line.split(|c: char| c == ',' || c.is_whitespace())
+                            .filter(|w| !w.is_empty())
+                            .map(|w| w.to_string()),
+                    );
+                }
+            }
+        }
+        if answers.len() != boards {
+            return Err(format!("Expected {} answers, found {}", boards, answers.len()).into());
+        }
+
+        for answer in &answers {
+            let answer = alphabet.normalize(answer);
+            if answer.chars().count() != length {
+                return Err(format!("The answer does not have {} letters", length).into());
             }
+            check_answer(&answer, &alphabet)?;
         }
-        let answer = answer.trim().to_uppercase();
-
-        let mut chances_used = 0usize;
-        let mut alphabet_state = [Status::UNKNOWN; ALPHABET_SIZE];
-
-        // 进行一轮猜测
-        let status = loop {
-            let mut guess = String::new();
-            io::stdin().read_line(&mut guess)?;
-            let guess = guess.trim().to_uppercase();
-
-            // 判断是否为合法输入
-            // 1) 在单词库中
-            // 2) 如果为 hard mode，则需要满足条件
-            if is_valid(
-                &guess,
-                config.difficult,
-                &saved_guessed_strings.last(),
-                &saved_word_state.last(),
+
+        let mut rating = None;
+        let mut game = if config.reverse {
+            // 反向模式，目标颜色由种子和日期生成
+            let mut rng = StdRng::seed_from_u64(puzzle_seed(&config, bias));
+            let puzzle = Puzzle::generate(
+                &answers[0],
                 &acceptable_word_list,
-            ) {
-                chances_used += 1;
-
-                saved_guessed_strings.push(guess.to_string());
-                all_guesses_strings.push(guess.to_string());
-                let mut word_state = [Status::UNKNOWN; WORD_LENGTH];
-
-                // 更新单词状态和字母表状态
-                update_state(&guess, &answer, &mut word_state, &mut alphabet_state);
-                saved_word_state.push(word_state);
-                saved_alphabet_state.push(alphabet_state);
-
-                // 判断是否为交互模式
-                match is_tty {
-                    true => print_state_tty(
-                        &saved_guessed_strings,
-                        &saved_word_state,
-                        &saved_alphabet_state,
-                    ),
-                    false => print_state_not_tty(&word_state, &alphabet_state),
+                rules.max_guesses - 1,
+                &alphabet,
+                &mut rng,
+            );
+            if puzzle.rows.is_empty() {
+                return Err("No target rows can be made for this answer".into());
+            }
+            let mut session =
+                ReverseSession::new(puzzle, rules.max_guesses, &acceptable_word_list, &alphabet);
+            play_reverse(&mut session, &input, &alphabet, is_tty)?;
+            Game {
+                answer: session.puzzle().answer.clone(),
+                guesses: session.attempts().to_vec(),
+                length,
+                max_guesses: rules.max_guesses,
+                answers: Vec::new(),
+                targets: session
+                    .puzzle()
+                    .rows
+                    .iter()
+                    .map(|row| row.iter().map(|status| status.to_char()).collect())
+                    .collect(),
+                lie_seed: None,
+                day: daily_day,
+                date: daily_date.clone(),
+                times: Vec::new(),
+                won: Some(session.outcome() == Some(Outcome::SUCCESS)),
+                skill: None,
+                luck: None,
+                metadata: Metadata::default(),
+            }
+        } else {
+            let mut session = MultiSession::new(&answers, rules, &acceptable_word_list, &alphabet)?;
+
+            // 进行一轮猜测
+            let mut timer = timed.then(|| {
+                Timer::new(
+                    config.time_limit.map(Duration::from_secs),
+                    config.guess_time_limit.map(Duration::from_secs),
+                )
+            });
+            // 预先填入的猜测走同样的检查和 update_state
+            for guess in &preset_guesses {
+                let feedback = submit(&mut session, &mut adversary, guess)
+                    .map_err(|error| format!("Preset guess {} is not valid: {}", guess, error))?;
+                if feedback.outcome.is_some() {
+                    return Err("The preset guesses already finish the game".into());
                 }
-                // 判断是否猜对
-                if guess == answer {
-                    break Outcome::SUCCESS;
-                } else if chances_used == TOTAL_CHANCES {
-                    break Outcome::FAILED;
+                if !is_tty {
+                    print_feedback_not_tty(&feedback);
                 }
-            } else {
-                println!("INVALID");
-                continue;
             }
+            if is_tty && !preset_guesses.is_empty() {
+                print_boards_tty(&session, &alphabet);
+            }
+
+            let lie_seed = config.fibble.then(|| puzzle_seed(&config, bias));
+            let mut liar = lie_seed.map(|seed| Liar::new(seed, &alphabet));
+            let mut show_hint = config.hint;
+            loop {
+                // 提示针对第一个未猜出的面板
+                // Fibble 中按显示的颜色推断候选词
+                let suggest = |session: &MultiSession, liar: &Option<Liar>| {
+                    let board = session.unsolved_boards().next()?;
+                    if board.guesses().is_empty() {
+                        return opening
+                            .get_or_init(|| solver::suggest(board, &final_word_list, source()))
+                            .clone();
+                    }
+                    match liar {
+                        Some(liar) => solver::suggest_among(
+                            board,
+                            solver::filter_candidates(
+                                &final_word_list,
+                                &liar.clues(),
+                                &alphabet,
+                                LIES_PER_ROW,
+                            ),
+                            source(),
+                        ),
+                        None => solver::suggest(board, &final_word_list, source()),
+                    }
+                };
+                if show_hint {
+                    print_hint(suggest(&session, &liar), is_tty);
+                    show_hint = false;
+                }
+                // 有时间限制时显示倒计时，等待输入超时也算超时
+                let remaining = timer.as_ref().and_then(Timer::remaining);
+                if let (Some(remaining), true) = (remaining, is_tty) {
+                    println!(
+                        "{} {:.0}s left",
+                        "TIME".bold().blue(),
+                        remaining.as_secs_f64().ceil()
+                    );
+                }
+                let guess = match remaining {
+                    Some(remaining) => input.read_line_timeout(remaining)?.unwrap_or_default(),
+                    None => input.read_line()?,
+                };
+
+                // 提示命令
+                if guess.trim() == "/hint" {
+                    print_hint(suggest(&session, &liar), is_tty);
+                    continue;
+                }
+
+                // 超时则本局失败
+                if let Some(Err(time_up)) = timer.as_ref().map(Timer::check) {
+                    match is_tty {
+                        true => println!("{} {}", "TIMEOUT".bold().red(), time_up),
+                        false => println!("TIMEOUT"),
+                    }
+                    break;
+                }
+
+                // 判断是否为合法输入
+                // 1) 在单词库中
+                // 2) 如果为 hard mode，则需要满足条件
+                // 3) 对抗模式下先选定保留的候选词
+                match submit(&mut session, &mut adversary, &guess) {
+                    Ok(feedback) => {
+                        show_hint = config.hint;
+                        if let Some(timer) = &mut timer {
+                            timer.lap();
+                        }
+
+                        // 判断是否为交互模式，Fibble 只显示改过一格的颜色
+                        match (&mut liar, is_tty) {
+                            (Some(liar), _) => {
+                                let guess = session.guesses().last().unwrap();
+                                let word_state = &feedback.boards[0].as_ref().unwrap().word_state;
+                                let row = liar.lie(guess, word_state, &alphabet);
+                                match is_tty {
+                                    true => print_state_tty(
+                                        liar.guesses(),
+                                        liar.rows(),
+                                        liar.alphabet_states(),
+                                        &alphabet,
+                                    ),
+                                    false => print_state_not_tty(&row, liar.alphabet_state()),
+                                }
+                            }
+                            (None, true) => print_boards_tty(&session, &alphabet),
+                            (None, false) => {
+                                print_feedback_not_tty(&feedback);
+                            }
+                        }
+                        // 判断是否猜对
+                        if feedback.outcome.is_some() {
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        // 交互模式下给出具体原因
+                        match is_tty {
+                            true => println!("{} {}", "INVALID".bold().red(), error),
+                            false => println!("INVALID"),
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // 交互模式下与参考求解器比较每一步
+            if is_tty && boards == 1 && liar.is_none() && adversary.is_none() {
+                rating = coach.rate(&session.boards()[0], source());
+            }
+            Game {
+                answer: session.boards()[0].answer().to_string(),
+                guesses: session.guesses().to_vec(),
+                length,
+                max_guesses: session.max_guesses(),
+                answers: match boards {
+                    1 => Vec::new(),
+                    _ => session.answers(),
+                },
+                targets: Vec::new(),
+                lie_seed,
+                day: daily_day,
+                date: daily_date.clone(),
+                times: timer.map(|timer| timer.laps().to_vec()).unwrap_or_default(),
+                won: None,
+                skill: rating.as_ref().map(GameRating::skill),
+                luck: rating.as_ref().map(GameRating::luck),
+                metadata: Metadata::default(),
+            }
+        };
+
+        // 记录复现对局所需的信息
+        game.metadata = Metadata {
+            started_at: Some(timestamp(started_at)),
+            ended_at: Some(timestamp(Utc::now())),
+            answer_mode: Some(if config.absurdle {
+                AnswerMode::Absurdle
+            } else if daily_day.is_some() {
+                AnswerMode::Daily
+            } else if config.random {
+                AnswerMode::Random
+            } else if config.word.is_some() {
+                AnswerMode::Word
+            } else {
+                AnswerMode::Input
+            }),
+            hard_mode: Some(config.difficult.into()),
+            seed: config.random.then(|| config.seed.unwrap_or(DEFAULT_SEED)),
+            random_day: (config.random && daily_day.is_none())
+                .then(|| config.day.unwrap_or(1).saturating_add(bias)),
+            word_lists: Some(format!(
+                "{:016x}",
+                word_lists_key(&acceptable_word_list, &final_word_list)
+            )),
         };
 
         // 完成一轮游戏，输出结果
+        let chances_used = game.guesses.len();
+        all_guesses_strings.extend(game.guesses.iter().cloned());
         total_rounds += 1;
-        match status {
-            Outcome::SUCCESS => {
+        match game.is_won() {
+            true => {
                 println!("CORRECT {}", chances_used);
                 win_rounds += 1;
                 win_guesses += chances_used;
+                speedrun_solved += 1;
             }
-            Outcome::FAILED => {
-                println!("FAILED {}", answer);
-            }
+            false => match game.answers.is_empty() {
+                true => println!("FAILED {}", game.answer),
+                false => println!("FAILED {}", game.answers.join(" ")),
+            },
+        }
+
+        if let Some(rating) = &rating {
+            commands::print_rating(rating);
         }
 
-        // 输出统计数据
+        // 更新存档
+        data.total_rounds = total_rounds;
+        data.games.push(game);
+        // 每局结束后立即存档
+        if let Some(state_file) = &mut state_file {
+            state_file.save(&data)?;
+        }
+
+        // 输出统计数据，交互模式下显示完整面板
         if config.stats {
-            println!(
-                "{} {} {:.2}",
-                win_rounds,
-                total_rounds - win_rounds,
-                match win_rounds {
-                    0 => 0f64,
-                    _ => win_guesses as f64 / win_rounds as f64,
-                }
-            );
             let top5 = find_most_frequent_strings(&all_guesses_strings, TOP_N);
-
-            for (i, (word, total)) in top5.iter().enumerate() {
-                print!("{} {}", word, total);
-                if i < top5.len() - 1 {
-                    print!(" ");
-                } else {
-                    println!();
+            if is_tty {
+                commands::print_stats_panel(&Stats::from_games(&data.games), &top5);
+                commands::print_allowance_summaries(&data.summary_by_max_guesses());
+                if let Some(median) = data.median_solve_time() {
+                    println!("Median solve time: {:.2}s", median);
+                }
+            } else {
+                println!(
+                    "{} {} {:.2}",
+                    win_rounds,
+                    total_rounds - win_rounds,
+                    match win_rounds {
+                        0 => 0f64,
+                        _ => win_guesses as f64 / win_rounds as f64,
+                    }
+                );
+                for (i, (word, total)) in top5.iter().enumerate() {
+                    print!("{} {}", word, total);
+                    if i < top5.len() - 1 {
+                        print!(" ");
+                    } else {
+                        println!();
+                    }
                 }
             }
         }
 
-        // 更新存档
-        if config.state != None {
-            data.total_rounds = total_rounds;
-            data.games.push(Game {
-                answer: answer.to_string(),
-                guesses: saved_guessed_strings.clone(),
-            });
+        // 竞速模式不询问，完成 K 局后报告总用时
+        if let Some(speedrun) = config.speedrun {
+            bias += 1;
+            if bias < speedrun {
+                continue;
+            }
+            let total = speedrun_started.elapsed().as_secs_f64();
+            match is_tty {
+                true => println!(
+                    "{} {}/{} solved in {:.2}s",
+                    "SPEEDRUN".bold().blue(),
+                    speedrun_solved,
+                    speedrun,
+                    total
+                ),
+                false => println!("SPEEDRUN {} {} {:.2}", speedrun_solved, speedrun, total),
+            }
+            break;
         }
 
-        // 是否继续游戏
-        if config.word != None {
+        // 是否继续游戏，每日模式只有一局
+        if config.word.is_some() || daily_day.is_some() {
             break;
         }
-        let mut option = String::new();
-        io::stdin().read_line(&mut option)?;
+        let option = input.read_line()?;
         let option = option.trim().to_uppercase();
 
         match option.as_str() {
@@ -423,13 +895,5 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // 进行存档
-    if config.state != None {
-        fs::write(
-            config.state.unwrap(),
-            serde_json::to_string_pretty(&data).unwrap(),
-        )?;
-    }
-
     Ok(())
 }
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::alphabet::Alphabet;
+use crate::game::score_guess;
+use crate::solver::feedback;
+use crate::status::Status;
+
+/// A feedback row encoded in base 3, first letter in the lowest digit
+pub type Pattern = u32;
+
+/// Longest words whose patterns fit in one byte of the matrix (3^5 = 243)
+pub const MAX_MATRIX_LENGTH: usize = 5;
+
+const MAGIC: &[u8; 8] = b"WDLFBM01";
+const HEADER_LEN: usize = 24;
+
+/// Encodes the colours of a guess, treating unknown tiles as red
+pub fn encode(word_state: &[Status]) -> Pattern {
+    word_state.iter().rev().fold(0, |acc, status| {
+        acc * 3
+            + match status {
+                Status::GREEN => 2,
+                Status::YELLOW => 1,
+                Status::RED | Status::UNKNOWN => 0,
+            }
+    })
+}
+
+/// Decodes a pattern of `length` tiles
+pub fn decode(mut pattern: Pattern, length: usize) -> Vec<Status> {
+    (0..length)
+        .map(|_| {
+            let status = match pattern % 3 {
+                2 => Status::GREEN,
+                1 => Status::YELLOW,
+                _ => Status::RED,
+            };
+            pattern /= 3;
+            status
+        })
+        .collect()
+}
+
+/// Anything that can tell the feedback of a guess against an answer
+pub trait FeedbackSource {
+    fn pattern(&self, guess: &str, answer: &str) -> Pattern;
+}
+
+/// Scores pairs with the game's own rules, without allocating for words that fit the matrix
+impl FeedbackSource for Alphabet {
+    fn pattern(&self, guess: &str, answer: &str) -> Pattern {
+        let length = answer.chars().count();
+        if length > MAX_MATRIX_LENGTH {
+            return encode(&feedback(guess, answer, self));
+        }
+        let mut word_state = [Status::UNKNOWN; MAX_MATRIX_LENGTH];
+        score_guess(guess, answer, &mut word_state[..length]);
+        encode(&word_state[..length])
+    }
+}
+
+/// Precomputed feedback of every guess against every answer, one byte per pair
+#[derive(Clone, Debug)]
+pub struct FeedbackMatrix {
+    alphabet: Alphabet,
+    guess_index: HashMap<String, usize>,
+    answer_index: HashMap<String, usize>,
+    answers: usize,
+    data: Vec<u8>,
+}
+
+impl FeedbackMatrix {
+    /// Computes the matrix with the game's scorer, splitting the guesses between threads
+    pub fn build(
+        guesses: &[String],
+        answers: &[String],
+        alphabet: &Alphabet,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        check_length(guesses, answers)?;
+        let mut data = vec![0u8; guesses.len() * answers.len()];
+        if !answers.is_empty() {
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+            let rows_per_thread = ((guesses.len() + threads - 1) / threads).max(1);
+            std::thread::scope(|scope| {
+                for (rows, chunk) in guesses
+                    .chunks(rows_per_thread)
+                    .zip(data.chunks_mut(rows_per_thread * answers.len()))
+                {
+                    scope.spawn(move || {
+                        for (guess, row) in rows.iter().zip(chunk.chunks_mut(answers.len())) {
+                            for (answer, cell) in answers.iter().zip(row.iter_mut()) {
+                                *cell = alphabet.pattern(guess, answer) as u8;
+                            }
+                        }
+                    });
+                }
+            });
+        }
+        Ok(FeedbackMatrix::from_data(guesses, answers, alphabet, data))
+    }
+
+    /// Loads a matrix saved by [`FeedbackMatrix::save`] for the same word lists
+    pub fn load(
+        path: &Path,
+        guesses: &[String],
+        answers: &[String],
+        alphabet: &Alphabet,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut content = fs::read(path)?;
+        if content.len() < HEADER_LEN || &content[..8] != MAGIC {
+            return Err(format!("{} is not a feedback matrix", path.display()).into());
+        }
+        let key = u64::from_le_bytes(content[8..16].try_into()?);
+        let rows = u32::from_le_bytes(content[16..20].try_into()?) as usize;
+        let columns = u32::from_le_bytes(content[20..24].try_into()?) as usize;
+        if key != word_lists_key(guesses, answers)
+            || rows != guesses.len()
+            || columns != answers.len()
+            || content.len() != HEADER_LEN + rows * columns
+        {
+            return Err(format!("{} was built for other word lists", path.display()).into());
+        }
+        let data = content.split_off(HEADER_LEN);
+        Ok(FeedbackMatrix::from_data(guesses, answers, alphabet, data))
+    }
+
+    /// Writes the matrix to a cache file
+    pub fn save(&self, path: &Path, guesses: &[String], answers: &[String]) -> std::io::Result<()> {
+        let mut content = Vec::with_capacity(HEADER_LEN + self.data.len());
+        content.extend_from_slice(MAGIC);
+        content.extend_from_slice(&word_lists_key(guesses, answers).to_le_bytes());
+        content.extend_from_slice(&(guesses.len() as u32).to_le_bytes());
+        content.extend_from_slice(&(answers.len() as u32).to_le_bytes());
+        content.extend_from_slice(&self.data);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+    }
+
+    /// Loads the matrix from `cache_dir`, building and saving it if it is missing or stale
+    pub fn load_or_build(
+        cache_dir: &Path,
+        guesses: &[String],
+        answers: &[String],
+        alphabet: &Alphabet,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = cache_path(cache_dir, guesses, answers);
+        if let Ok(matrix) = FeedbackMatrix::load(&path, guesses, answers, alphabet) {
+            return Ok(matrix);
+        }
+        let matrix = FeedbackMatrix::build(guesses, answers, alphabet)?;
+        matrix.save(&path, guesses, answers)?;
+        Ok(matrix)
+    }
+
+    fn from_data(
+        guesses: &[String],
+        answers: &[String],
+        alphabet: &Alphabet,
+        data: Vec<u8>,
+    ) -> Self {
+        FeedbackMatrix {
+            alphabet: alphabet.clone(),
+            guess_index: index_words(guesses),
+            answer_index: index_words(answers),
+            answers: answers.len(),
+            data,
+        }
+    }
+
+    /// Pattern of the guess and answer at the given positions of the word lists
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        self.data[guess * self.answers + answer] as Pattern
+    }
+}
+
+/// Looks the pair up, falling back to the game's scorer for words outside the lists
+impl FeedbackSource for FeedbackMatrix {
+    fn pattern(&self, guess: &str, answer: &str) -> Pattern {
+        match (self.guess_index.get(guess), self.answer_index.get(answer)) {
+            (Some(&guess), Some(&answer)) => self.get(guess, answer),
+            _ => self.alphabet.pattern(guess, answer),
+        }
+    }
+}
+
+/// Cache file of the matrix for two word lists
+pub fn cache_path(cache_dir: &Path, guesses: &[String], answers: &[String]) -> PathBuf {
+    cache_dir.join(format!(
+        "feedback-{:016x}.bin",
+        word_lists_key(guesses, answers)
+    ))
+}
+
+/// Stable FNV-1a hash of both word lists
+pub fn word_lists_key(guesses: &[String], answers: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for word in guesses
+        .iter()
+        .chain(std::iter::once(&String::new()))
+        .chain(answers)
+    {
+        for byte in word.bytes().chain(std::iter::once(b'\n')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+fn index_words(words: &[String]) -> HashMap<String, usize> {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.clone(), i))
+        .collect()
+}
+
+fn check_length(guesses: &[String], answers: &[String]) -> Result<(), String> {
+    match guesses
+        .iter()
+        .chain(answers)
+        .find(|word| word.chars().count() > MAX_MATRIX_LENGTH)
+    {
+        Some(word) => Err(format!(
+            "Feedback matrices only support words of up to {} letters, found {}",
+            MAX_MATRIX_LENGTH, word
+        )),
+        None => Ok(()),
+    }
+}
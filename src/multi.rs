@@ -0,0 +1,125 @@
+use crate::alphabet::Alphabet;
+use crate::game::{AnswerError, Feedback, GuessError, Outcome, Rules, Session};
+
+/// Feedback of a guess on every board
+#[derive(Clone, Debug)]
+pub struct MultiFeedback {
+    /// `None` for boards that were already solved before this guess
+    pub boards: Vec<Option<Feedback>>,
+    /// `Some` if this guess finished the game
+    pub outcome: Option<Outcome>,
+}
+
+/// Several boards played at once with shared guesses (Dordle, Quordle, Octordle)
+///
+/// Each extra board adds one guess to the allowance of `rules`. With a single
+/// board this plays exactly like a [`Session`].
+#[derive(Clone, Debug)]
+pub struct MultiSession<'a> {
+    boards: Vec<Session<'a>>,
+    guesses: Vec<String>,
+    max_guesses: usize,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> MultiSession<'a> {
+    pub fn new(
+        answers: &[String],
+        rules: Rules,
+        acceptable_set: &'a [String],
+        alphabet: &'a Alphabet,
+    ) -> Result<Self, AnswerError> {
+        let max_guesses = rules.max_guesses + answers.len().saturating_sub(1);
+        let board_rules = Rules {
+            max_guesses,
+            ..rules
+        };
+        Ok(MultiSession {
+            boards: answers
+                .iter()
+                .map(|answer| Session::new(answer, board_rules, acceptable_set, alphabet))
+                .collect::<Result<_, _>>()?,
+            guesses: Vec::new(),
+            max_guesses,
+            outcome: None,
+        })
+    }
+
+    /// Checks whether a word would be accepted on every unsolved board
+    pub fn check(&self, word: &str) -> Result<String, GuessError> {
+        if self.outcome.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        let mut checked = Err(GuessError::GameOver);
+        for board in self.unsolved_boards() {
+            checked = Ok(board.check(word)?);
+        }
+        checked
+    }
+
+    /// Submits a guess to every unsolved board
+    pub fn guess(&mut self, word: &str) -> Result<MultiFeedback, GuessError> {
+        let word = self.check(word)?;
+        let boards: Vec<Option<Feedback>> = self
+            .boards
+            .iter_mut()
+            .map(|board| match board.outcome() {
+                Some(_) => None,
+                None => Some(
+                    board
+                        .guess(&word)
+                        .expect("guess was checked on every board"),
+                ),
+            })
+            .collect();
+        self.guesses.push(word);
+
+        if self
+            .boards
+            .iter()
+            .all(|board| board.outcome() == Some(Outcome::SUCCESS))
+        {
+            self.outcome = Some(Outcome::SUCCESS);
+        } else if self.guesses.len() == self.max_guesses {
+            self.outcome = Some(Outcome::FAILED);
+        }
+        Ok(MultiFeedback {
+            boards,
+            outcome: self.outcome,
+        })
+    }
+
+    pub fn boards(&self) -> &[Session<'a>] {
+        &self.boards
+    }
+
+    pub fn boards_mut(&mut self) -> &mut [Session<'a>] {
+        &mut self.boards
+    }
+
+    /// Boards whose answer has not been found yet
+    pub fn unsolved_boards(&self) -> impl Iterator<Item = &Session<'a>> {
+        self.boards.iter().filter(|board| board.outcome().is_none())
+    }
+
+    pub fn answers(&self) -> Vec<String> {
+        self.boards
+            .iter()
+            .map(|board| board.answer().to_string())
+            .collect()
+    }
+
+    pub fn guesses(&self) -> &[String] {
+        &self.guesses
+    }
+
+    /// Number of guesses allowed for all boards together
+    pub fn max_guesses(&self) -> usize {
+        self.max_guesses
+    }
+
+    /// `None` while the game is still in progress
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+}
@@ -0,0 +1,163 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::alphabet::Alphabet;
+use crate::game::{GuessError, Outcome};
+use crate::matrix::encode;
+use crate::solver::feedback;
+use crate::status::Status;
+
+/// A reverse Wordle puzzle: find words giving each target row against the answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub answer: String,
+    pub rows: Vec<Vec<Status>>,
+}
+
+impl Puzzle {
+    /// Draws up to `rows` distinct patterns that acceptable words give against `answer`
+    ///
+    /// Every row can be reproduced by at least one acceptable word. Rows are
+    /// ordered from the fewest to the most revealed tiles, like a real game.
+    pub fn generate<R: Rng>(
+        answer: &str,
+        acceptable_set: &[String],
+        rows: usize,
+        alphabet: &Alphabet,
+        rng: &mut R,
+    ) -> Puzzle {
+        let answer = alphabet.normalize(answer);
+        let mut words: Vec<&String> = acceptable_set.iter().collect();
+        words.shuffle(rng);
+
+        let mut patterns: Vec<Vec<Status>> = Vec::new();
+        for word in words {
+            if patterns.len() == rows {
+                break;
+            }
+            let pattern = feedback(word, &answer, alphabet);
+            if pattern.iter().all(|&status| status == Status::GREEN) || patterns.contains(&pattern)
+            {
+                continue;
+            }
+            patterns.push(pattern);
+        }
+        patterns.sort_by_key(|pattern| {
+            let greens = pattern.iter().filter(|&&s| s == Status::GREEN).count();
+            (greens, encode(pattern))
+        });
+        Puzzle {
+            answer,
+            rows: patterns,
+        }
+    }
+}
+
+/// Feedback of a word entered for the current row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReverseFeedback {
+    /// Pattern the word gives against the answer
+    pub word_state: Vec<Status>,
+    /// Whether it is the pattern of the current row
+    pub matched: bool,
+    /// `Some` if this word finished the puzzle
+    pub outcome: Option<Outcome>,
+}
+
+/// A reverse puzzle being solved row by row
+#[derive(Clone, Debug)]
+pub struct ReverseSession<'a> {
+    puzzle: Puzzle,
+    acceptable_set: &'a [String],
+    alphabet: &'a Alphabet,
+    /// Words that reproduced the rows solved so far
+    solutions: Vec<String>,
+    /// Every accepted word, matching or not
+    attempts: Vec<String>,
+    max_misses: usize,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> ReverseSession<'a> {
+    /// The puzzle fails once `max_misses` words did not match their row
+    pub fn new(
+        puzzle: Puzzle,
+        max_misses: usize,
+        acceptable_set: &'a [String],
+        alphabet: &'a Alphabet,
+    ) -> Self {
+        let outcome = match puzzle.rows.is_empty() {
+            true => Some(Outcome::SUCCESS),
+            false => None,
+        };
+        ReverseSession {
+            puzzle,
+            acceptable_set,
+            alphabet,
+            solutions: Vec::new(),
+            attempts: Vec::new(),
+            max_misses,
+            outcome,
+        }
+    }
+
+    /// Enters a word for the current row
+    pub fn guess(&mut self, word: &str) -> Result<ReverseFeedback, GuessError> {
+        if self.outcome.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        let word = self.alphabet.normalize(word);
+        let length = self.puzzle.answer.chars().count();
+        if word.chars().count() != length {
+            return Err(GuessError::WrongLength(length));
+        }
+        if !self.acceptable_set.contains(&word) {
+            return Err(GuessError::NotInWordList);
+        }
+
+        // 用 update_state 反向检查是否得到目标颜色
+        let word_state = feedback(&word, &self.puzzle.answer, self.alphabet);
+        let matched = word_state == self.puzzle.rows[self.solutions.len()];
+        if matched {
+            self.solutions.push(word.clone());
+        }
+        self.attempts.push(word);
+
+        if self.solutions.len() == self.puzzle.rows.len() {
+            self.outcome = Some(Outcome::SUCCESS);
+        } else if self.misses() == self.max_misses {
+            self.outcome = Some(Outcome::FAILED);
+        }
+        Ok(ReverseFeedback {
+            word_state,
+            matched,
+            outcome: self.outcome,
+        })
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    pub fn solutions(&self) -> &[String] {
+        &self.solutions
+    }
+
+    pub fn attempts(&self) -> &[String] {
+        &self.attempts
+    }
+
+    /// Words that did not reproduce their row
+    pub fn misses(&self) -> usize {
+        self.attempts.len() - self.solutions.len()
+    }
+
+    pub fn max_misses(&self) -> usize {
+        self.max_misses
+    }
+
+    /// `None` while the puzzle is still in progress
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::alphabet::Alphabet;
+use crate::game::{update_state, Session};
+use crate::matrix::{FeedbackSource, Pattern, MAX_MATRIX_LENGTH};
+use crate::status::Status;
+
+/// Entropies closer than this are treated as ties
+const TOLERANCE: f64 = 1e-9;
+
+/// A suggested next guess
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub word: String,
+    /// Expected information of the guess in bits
+    pub entropy: f64,
+    /// Number of answers still consistent with the clues
+    pub candidates: usize,
+}
+
+/// Returns the colours `guess` would get against `answer`
+pub fn feedback(guess: &str, answer: &str, alphabet: &Alphabet) -> Vec<Status> {
+    let mut word_state = vec![Status::UNKNOWN; answer.chars().count()];
+    let mut alphabet_state = vec![Status::UNKNOWN; alphabet.len()];
+    update_state(
+        guess,
+        answer,
+        &mut word_state,
+        &mut alphabet_state,
+        alphabet,
+    );
+    word_state
+}
+
+/// Returns the answers that would have produced every recorded feedback
+pub fn remaining_candidates<'a>(
+    final_set: &'a [String],
+    guesses: &[String],
+    word_states: &[Vec<Status>],
+    alphabet: &Alphabet,
+) -> Vec<&'a String> {
+    final_set
+        .iter()
+        .filter(|answer| {
+            guesses
+                .iter()
+                .zip(word_states)
+                .all(|(guess, word_state)| feedback(guess, answer, alphabet) == *word_state)
+        })
+        .collect()
+}
+
+/// A guess together with the feedback it received
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clue {
+    pub guess: String,
+    pub pattern: Vec<Status>,
+}
+
+impl Clue {
+    /// Parses a clue written as `GUESS:PATTERN`, e.g. `CRANE:RYGRR`
+    pub fn parse(clue: &str, alphabet: &Alphabet) -> Result<Clue, String> {
+        let (guess, pattern) = clue
+            .split_once(':')
+            .ok_or_else(|| format!("Clue {} is not written as GUESS:PATTERN", clue))?;
+        let guess = alphabet.normalize(guess);
+        if !alphabet.contains_word(&guess) {
+            return Err(format!("Guess {} has letters outside the alphabet", guess));
+        }
+        let pattern = pattern
+            .trim()
+            .chars()
+            .map(|c| match Status::from_char(c) {
+                Some(Status::UNKNOWN) | None => Err(format!(
+                    "Pattern of {} may only contain G, Y and R, found {}",
+                    guess, c
+                )),
+                Some(status) => Ok(status),
+            })
+            .collect::<Result<Vec<Status>, String>>()?;
+        if pattern.len() != guess.chars().count() {
+            return Err(format!(
+                "Pattern of {} does not have {} letters",
+                guess,
+                guess.chars().count()
+            ));
+        }
+        Ok(Clue { guess, pattern })
+    }
+}
+
+/// Whether `shown` is `truth` with exactly `lies` tiles changed
+pub fn matches_with_lies(truth: &[Status], shown: &[Status], lies: usize) -> bool {
+    truth.len() == shown.len() && truth.iter().zip(shown).filter(|(t, s)| t != s).count() == lies
+}
+
+/// Returns the answers consistent with every clue, each clue having exactly `lies` wrong tiles
+pub fn filter_candidates<'a>(
+    final_set: &'a [String],
+    clues: &[Clue],
+    alphabet: &Alphabet,
+    lies: usize,
+) -> Vec<&'a String> {
+    final_set
+        .iter()
+        .filter(|answer| {
+            clues.iter().all(|clue| {
+                clue.pattern.len() == answer.chars().count()
+                    && matches_with_lies(
+                        &feedback(&clue.guess, answer, alphabet),
+                        &clue.pattern,
+                        lies,
+                    )
+            })
+        })
+        .collect()
+}
+
+/// Groups the candidates by the feedback `guess` would get against them
+pub fn buckets(
+    guess: &str,
+    candidates: &[&String],
+    source: &dyn FeedbackSource,
+) -> HashMap<Pattern, usize> {
+    let mut buckets: HashMap<Pattern, usize> = HashMap::new();
+    for answer in candidates {
+        *buckets.entry(source.pattern(guess, answer)).or_insert(0) += 1;
+    }
+    buckets
+}
+
+/// Sizes of the non-empty buckets of [`buckets`], counted in an array for short words
+pub fn bucket_sizes(
+    guess: &str,
+    candidates: &[&String],
+    source: &dyn FeedbackSource,
+) -> Vec<usize> {
+    if guess.chars().count() > MAX_MATRIX_LENGTH {
+        return buckets(guess, candidates, source).into_values().collect();
+    }
+    // 五个字母以内的模式不超过 3^5 种
+    let mut counts = [0usize; 243];
+    for answer in candidates {
+        counts[source.pattern(guess, answer) as usize] += 1;
+    }
+    counts.into_iter().filter(|&count| count > 0).collect()
+}
+
+/// Expected information in bits revealed by `guess` over the candidates
+pub fn entropy(guess: &str, candidates: &[&String], source: &dyn FeedbackSource) -> f64 {
+    let total = candidates.len() as f64;
+    bucket_sizes(guess, candidates, source)
+        .into_iter()
+        .map(|count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Returns the guess from `pool` with the highest score and that score
+///
+/// Ties go to words that could still be the answer, then to the alphabetically
+/// first word.
+pub fn best_guess<'w, I, F>(pool: I, candidates: &[&String], score: F) -> Option<(&'w String, f64)>
+where
+    I: IntoIterator<Item = &'w String>,
+    F: Fn(&str) -> f64,
+{
+    let candidate_set: HashSet<&String> = candidates.iter().copied().collect();
+    let mut best: Option<(f64, bool, &String)> = None;
+    for word in pool {
+        let score = score(word);
+        let is_candidate = candidate_set.contains(word);
+        let better = match best {
+            None => true,
+            Some((best_score, best_is_candidate, best_word)) => {
+                score > best_score + TOLERANCE
+                    || ((score - best_score).abs() <= TOLERANCE
+                        && (is_candidate, std::cmp::Reverse(word))
+                            > (best_is_candidate, std::cmp::Reverse(best_word)))
+            }
+        };
+        if better {
+            best = Some((score, is_candidate, word));
+        }
+    }
+    best.map(|(score, _, word)| (word, score))
+}
+
+/// Suggests the acceptable guess with the highest entropy over the remaining answers
+///
+/// Only guesses the session would accept are considered, so difficult mode is
+/// respected. `source` can be a [`crate::matrix::FeedbackMatrix`] to speed up scoring.
+pub fn suggest(
+    session: &Session,
+    final_set: &[String],
+    source: &dyn FeedbackSource,
+) -> Option<Suggestion> {
+    let candidates = remaining_candidates(
+        final_set,
+        session.guesses(),
+        session.word_states(),
+        session.alphabet(),
+    );
+    suggest_among(session, candidates, source)
+}
+
+/// Suggests the acceptable guess with the highest entropy over the given candidates
+///
+/// Used when the session's own feedback is not what the player saw, e.g. in Fibble.
+pub fn suggest_among(
+    session: &Session,
+    candidates: Vec<&String>,
+    source: &dyn FeedbackSource,
+) -> Option<Suggestion> {
+    // 候选词不超过两个时直接猜其中之一
+    if candidates.len() <= 2 {
+        return candidates.first().map(|word| Suggestion {
+            word: word.to_string(),
+            entropy: if candidates.len() == 2 { 1f64 } else { 0f64 },
+            candidates: candidates.len(),
+        });
+    }
+
+    let pool = session
+        .acceptable_set()
+        .iter()
+        .filter(|word| session.check(word).is_ok());
+    best_guess(pool, &candidates, |word| entropy(word, &candidates, source)).map(|(word, score)| {
+        Suggestion {
+            word: word.to_string(),
+            entropy: score,
+            candidates: candidates.len(),
+        }
+    })
+}
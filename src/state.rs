@@ -1,9 +1,30 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
+use fs2::FileExt;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::game::{DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH};
+use crate::hard_mode::HardMode;
+
+/// Schema version of the state files written by this build
+pub const STATE_VERSION: u32 = 2;
+
+/// Files without a `version` key were written before the schema was versioned
+const UNVERSIONED: u32 = 1;
+
+/// `MIGRATIONS[i]` upgrades a state file from version `i + UNVERSIONED` to the next one
+const MIGRATIONS: [fn(&mut Map<String, Value>); (STATE_VERSION - UNVERSIONED) as usize] =
+    [migrate_v1];
 
 #[derive(Debug, Serialize)]
 pub struct State {
+    /// Always [`STATE_VERSION`] once loaded, older files are migrated
+    pub version: u32,
     pub total_rounds: usize,
     pub games: Vec<Game>,
 }
@@ -11,6 +32,7 @@ pub struct State {
 impl Default for State {
     fn default() -> Self {
         State {
+            version: STATE_VERSION,
             total_rounds: 0,
             games: Vec::new(),
         }
@@ -21,6 +43,444 @@ impl Default for State {
 pub struct Game {
     pub answer: String,
     pub guesses: Vec<String>,
+    /// Word length the game was played with, omitted for the default length
+    #[serde(default = "default_length", skip_serializing_if = "is_default_length")]
+    pub length: usize,
+    /// Number of guesses allowed in the game, omitted for the default allowance
+    #[serde(
+        default = "default_max_guesses",
+        skip_serializing_if = "is_default_max_guesses"
+    )]
+    pub max_guesses: usize,
+    /// Every answer of a multi-board game, omitted for single-board games
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<String>,
+    /// Target rows of a reverse puzzle, e.g. `RYGRR`, omitted for normal games
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+    /// Seed of the lies told in a Fibble game, omitted for other games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lie_seed: Option<u64>,
+    /// Day number of a daily puzzle, omitted for other games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day: Option<usize>,
+    /// Date of a daily puzzle, YYYY-MM-DD, omitted for other games and older daily games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// Seconds taken by each guess of a timed game, omitted for untimed games
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub times: Vec<f64>,
+    /// Result of games that are not won by guessing the answer, e.g. reverse puzzles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub won: Option<bool>,
+    /// Average skill of the guesses against the reference solver, see [`crate::coach`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill: Option<f64>,
+    /// Answers fewer than expected left by the feedback over the whole game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub luck: Option<f64>,
+    /// Details needed to reproduce the game, missing from games saved by older versions
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+/// How the answer of a game was chosen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerMode {
+    /// Given with `--word`
+    Word,
+    /// Drawn with the seed and the day
+    Random,
+    /// The puzzle of a date
+    Daily,
+    /// Read from standard input
+    Input,
+    /// Chosen by the adversary as the game went on
+    Absurdle,
+}
+
+/// Difficult mode a game was played in
+///
+/// Saved as the level, or `false` when off. Older games only recorded
+/// whether difficult mode was on, so `true` is still read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Difficulty {
+    Level(HardMode),
+    Enabled(bool),
+}
+
+impl From<Option<HardMode>> for Difficulty {
+    fn from(level: Option<HardMode>) -> Self {
+        match level {
+            Some(level) => Difficulty::Level(level),
+            None => Difficulty::Enabled(false),
+        }
+    }
+}
+
+/// Optional details of a saved game, every field is omitted when unknown
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Metadata {
+    /// Start of the game, RFC 3339 in UTC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// End of the game, RFC 3339 in UTC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    #[serde(default, rename = "mode", skip_serializing_if = "Option::is_none")]
+    pub answer_mode: Option<AnswerMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_mode: Option<Difficulty>,
+    /// Seed of random and daily answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Day index the random answer was drawn for, daily games use `Game::day`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random_day: Option<usize>,
+    /// Hash of the acceptable and final word lists, see [`crate::matrix::word_lists_key`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_lists: Option<String>,
+}
+
+impl Game {
+    /// Whether every answer was found
+    pub fn is_won(&self) -> bool {
+        if let Some(won) = self.won {
+            return won;
+        }
+        match self.answers.is_empty() {
+            true => self.guesses.last() == Some(&self.answer),
+            false => self
+                .answers
+                .iter()
+                .all(|answer| self.guesses.contains(answer)),
+        }
+    }
+
+    /// Every answer with the guesses played on its board, up to the one that found it
+    pub fn boards(&self) -> Vec<(&str, &[String])> {
+        let answers = match self.answers.is_empty() {
+            true => std::slice::from_ref(&self.answer),
+            false => &self.answers[..],
+        };
+        answers
+            .iter()
+            .map(|answer| {
+                let played = match self.guesses.iter().position(|guess| guess == answer) {
+                    Some(position) => &self.guesses[..=position],
+                    None => &self.guesses[..],
+                };
+                (answer.as_str(), played)
+            })
+            .collect()
+    }
+
+    /// Name of the mode the game was played in, told from the recorded fields
+    pub fn mode(&self) -> &'static str {
+        if !self.targets.is_empty() {
+            "reverse"
+        } else if !self.answers.is_empty() {
+            "multi-board"
+        } else if self.lie_seed.is_some() {
+            "fibble"
+        } else if self.metadata.answer_mode == Some(AnswerMode::Absurdle) {
+            "absurdle"
+        } else if self.day.is_some() {
+            "daily"
+        } else if !self.times.is_empty() {
+            "timed"
+        } else {
+            "normal"
+        }
+    }
+
+    /// Seconds taken by the whole game, `None` if it was not timed
+    pub fn elapsed(&self) -> Option<f64> {
+        match self.times.is_empty() {
+            true => None,
+            false => Some(self.times.iter().sum()),
+        }
+    }
+}
+
+/// Aggregated results of a group of games
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub wins: usize,
+    pub losses: usize,
+    /// Total guesses used in the games that were won
+    pub win_guesses: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, game: &Game) {
+        if game.is_won() {
+            self.wins += 1;
+            self.win_guesses += game.guesses.len();
+        } else {
+            self.losses += 1;
+        }
+    }
+
+    /// Average guesses of the games that were won
+    pub fn average_guesses(&self) -> f64 {
+        match self.wins {
+            0 => 0f64,
+            _ => self.win_guesses as f64 / self.wins as f64,
+        }
+    }
+
+    /// Percentage of games won
+    pub fn win_rate(&self) -> f64 {
+        match self.wins + self.losses {
+            0 => 0f64,
+            total => self.wins as f64 * 100f64 / total as f64,
+        }
+    }
+}
+
+impl State {
+    /// Parses a state file, upgrading older versions to [`STATE_VERSION`]
+    ///
+    /// Returns the state and the version the file was written with. Files of a
+    /// newer version are rejected instead of dropping what this build does not know.
+    pub fn from_json(content: &[u8]) -> Result<(State, u32), Box<dyn std::error::Error>> {
+        let mut value: Value = serde_json::from_slice(content)?;
+        let Value::Object(map) = &mut value else {
+            return Err("The state file is not a JSON object".into());
+        };
+        let version = match map.get("version") {
+            None => UNVERSIONED,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or("The version of the state file is not a number")?,
+        };
+        check_version(version)?;
+
+        // 逐个版本升级
+        for migration in &MIGRATIONS[(version - UNVERSIONED) as usize..] {
+            migration(map);
+        }
+        map.insert("version".to_string(), Value::from(STATE_VERSION));
+        Ok((serde_json::from_value(value)?, version))
+    }
+
+    /// Summarizes the games grouped by the number of guesses they allowed
+    pub fn summary_by_max_guesses(&self) -> BTreeMap<usize, Summary> {
+        let mut summaries: BTreeMap<usize, Summary> = BTreeMap::new();
+        for game in self.games.iter() {
+            summaries.entry(game.max_guesses).or_default().add(game);
+        }
+        summaries
+    }
+
+    /// Finds a game by its 1-based number or `last`
+    pub fn find_game(&self, selector: &str) -> Result<&Game, String> {
+        let game = match selector.trim() {
+            "last" => self.games.last(),
+            number => match number.parse::<usize>() {
+                Ok(number) if number > 0 => self.games.get(number - 1),
+                _ => return Err(format!("Game {} is neither a number nor last", selector)),
+            },
+        };
+        game.ok_or_else(|| format!("Game {} is not in the state file", selector))
+    }
+
+    /// Median time of the timed games that were won
+    pub fn median_solve_time(&self) -> Option<f64> {
+        let mut times: Vec<f64> = self
+            .games
+            .iter()
+            .filter(|game| game.is_won())
+            .filter_map(|game| game.elapsed())
+            .collect();
+        if times.is_empty() {
+            return None;
+        }
+        times.sort_by(f64::total_cmp);
+        let middle = times.len() / 2;
+        Some(match times.len() % 2 {
+            0 => (times[middle - 1] + times[middle]) / 2f64,
+            _ => times[middle],
+        })
+    }
+}
+
+/// A state file locked by this process until dropped
+///
+/// The lock is advisory and taken on a `.lock` file next to the state, because
+/// saving replaces the state file itself. The lock file is removed when the
+/// state file is dropped. Saves write a temporary file and rename it over the
+/// state, so an interrupted save never leaves half a file.
+#[derive(Debug)]
+pub struct StateFile {
+    path: PathBuf,
+    lock: File,
+    /// Content and version of a file that was upgraded, kept until the first save
+    original: Option<(Vec<u8>, u32)>,
+}
+
+impl StateFile {
+    /// Locks the state file and reads it; a missing file gives an empty state
+    pub fn open(path: &Path) -> Result<(StateFile, State), Box<dyn std::error::Error>> {
+        let lock_path = sibling_path(path, ".lock");
+        let lock = loop {
+            let lock = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&lock_path)?;
+            if let Err(error) = lock.try_lock_exclusive() {
+                if error.raw_os_error() == fs2::lock_contended_error().raw_os_error() {
+                    return Err(
+                        format!("{} is used by another wordle process", path.display()).into(),
+                    );
+                }
+                return Err(error.into());
+            }
+            // 上一个进程可能在我们加锁前删除了锁文件，这时要对新的锁文件重新加锁
+            if is_same_file(&lock, &lock_path)? {
+                break lock;
+            }
+        };
+
+        // 先建好 StateFile，读取失败时也会删除锁文件
+        let mut file = StateFile {
+            path: path.to_path_buf(),
+            lock,
+            original: None,
+        };
+        let state = match fs::read(path) {
+            Ok(content) => {
+                let (state, version) = State::from_json(&content)?;
+                if version < STATE_VERSION {
+                    file.original = Some((content, version));
+                }
+                state
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => State::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok((file, state))
+    }
+
+    /// Atomically replaces the file with the state, backing up an upgraded original first
+    pub fn save(&mut self, state: &State) -> io::Result<()> {
+        if let Some((content, version)) = self.original.take() {
+            fs::write(backup_path(&self.path, version), content)?;
+        }
+
+        // 先写临时文件再重命名，写到一半中断也不会损坏存档
+        let temporary = sibling_path(&self.path, &format!(".{}.tmp", std::process::id()));
+        let written = File::create(&temporary).and_then(|mut file| {
+            file.write_all(serde_json::to_string_pretty(state)?.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(error) = written.and_then(|()| fs::rename(&temporary, &self.path)) {
+            let _ = fs::remove_file(&temporary);
+            return Err(error);
+        }
+        sync_parent(&self.path)
+    }
+}
+
+impl Drop for StateFile {
+    /// Removes the lock file while still holding the lock, then releases it
+    fn drop(&mut self) {
+        let lock_path = sibling_path(&self.path, ".lock");
+        if is_same_file(&self.lock, &lock_path).unwrap_or(false) {
+            let _ = fs::remove_file(lock_path);
+        }
+    }
+}
+
+/// Whether `path` still names the open `file`
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let opened = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(named) => Ok(opened.dev() == named.dev() && opened.ino() == named.ino()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Whether `path` still names the open `file`; open files cannot be removed on this platform
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> io::Result<bool> {
+    Ok(path.exists())
+}
+
+/// Makes the rename of a file durable by syncing its directory
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+/// Directories cannot be opened for syncing on this platform
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// `path` with `suffix` appended to its file name
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(suffix);
+    PathBuf::from(sibling)
+}
+
+/// Where the original of a state file upgraded from `version` is kept, e.g. `state.json.v1.bak`
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    sibling_path(path, &format!(".v{}.bak", version))
+}
+
+/// Rejects versions this build cannot read
+fn check_version(version: u32) -> Result<(), String> {
+    match version {
+        UNVERSIONED..=STATE_VERSION => Ok(()),
+        _ if version > STATE_VERSION => Err(format!(
+            "The state file has version {}, but this build only reads up to version {}; please upgrade wordle",
+            version, STATE_VERSION
+        )),
+        _ => Err(format!("The state file has unknown version {}", version)),
+    }
+}
+
+/// Version 1 could omit `total_rounds`, which counts the saved games
+fn migrate_v1(state: &mut Map<String, Value>) {
+    if !state.contains_key("total_rounds") {
+        let games = state
+            .get("games")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        state.insert("total_rounds".to_string(), Value::from(games));
+    }
+}
+
+fn default_length() -> usize {
+    DEFAULT_WORD_LENGTH
+}
+
+fn is_default_length(length: &usize) -> bool {
+    *length == DEFAULT_WORD_LENGTH
+}
+
+fn default_max_guesses() -> usize {
+    DEFAULT_MAX_GUESSES
+}
+
+fn is_default_max_guesses(max_guesses: &usize) -> bool {
+    *max_guesses == DEFAULT_MAX_GUESSES
 }
 
 impl<'de> Deserialize<'de> for State {
@@ -31,6 +491,7 @@ impl<'de> Deserialize<'de> for State {
         #[derive(Debug, Deserialize)]
         #[serde(field_identifier, rename_all = "snake_case")]
         enum Field {
+            Version,
             TotalRounds,
             Games,
         }
@@ -48,11 +509,20 @@ impl<'de> Deserialize<'de> for State {
             where
                 V: serde::de::MapAccess<'de>,
             {
+                let mut version: Option<u32> = None;
                 let mut total_rounds: Option<usize> = None;
                 let mut games: Option<Vec<Game>> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
+                        Field::Version => {
+                            if version.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            let value = map.next_value()?;
+                            check_version(value).map_err(serde::de::Error::custom)?;
+                            version = Some(value);
+                        }
                         Field::TotalRounds => {
                             if total_rounds.is_some() {
                                 return Err(serde::de::Error::duplicate_field("total_rounds"));
@@ -71,7 +541,9 @@ impl<'de> Deserialize<'de> for State {
                 let total_rounds = total_rounds.unwrap_or_default();
                 let games = games.unwrap_or_default();
 
+                // 旧版本的内容在当前版本中仍然有效
                 Ok(State {
+                    version: STATE_VERSION,
                     total_rounds,
                     games,
                 })
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::game::DEFAULT_MAX_GUESSES;
+use crate::state::{Game, State, Summary};
+
+/// Statistics over a list of games, oldest first
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Stats {
+    pub played: usize,
+    pub wins: usize,
+    /// `distribution[i]` is the number of games won in `i + 1` guesses
+    pub distribution: Vec<usize>,
+    /// Games won in a row up to the last game
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// Games played in each mode, see [`Game::mode`]
+    pub modes: BTreeMap<String, usize>,
+    /// Average skill of the games rated against the reference solver
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill: Option<f64>,
+    #[serde(skip)]
+    summary: Summary,
+}
+
+impl Stats {
+    pub fn from_games(games: &[Game]) -> Self {
+        let mut stats = Stats {
+            distribution: vec![0; DEFAULT_MAX_GUESSES],
+            ..Stats::default()
+        };
+        for game in games {
+            stats.played += 1;
+            stats.summary.add(game);
+            *stats.modes.entry(game.mode().to_string()).or_insert(0) += 1;
+            if game.is_won() {
+                stats.wins += 1;
+                // 没有猜测就结束的对局不计入分布
+                let guesses = game.guesses.len();
+                if stats.distribution.len() < guesses {
+                    stats.distribution.resize(guesses, 0);
+                }
+                if guesses > 0 {
+                    stats.distribution[guesses - 1] += 1;
+                }
+                stats.current_streak += 1;
+                stats.max_streak = stats.max_streak.max(stats.current_streak);
+            } else {
+                stats.current_streak = 0;
+            }
+        }
+        // 只统计评过分的对局
+        let skills: Vec<f64> = games.iter().filter_map(|game| game.skill).collect();
+        if !skills.is_empty() {
+            stats.skill = Some(skills.iter().sum::<f64>() / skills.len() as f64);
+        }
+        stats
+    }
+
+    pub fn losses(&self) -> usize {
+        self.summary.losses
+    }
+
+    /// See [`Summary::win_rate`]
+    pub fn win_rate(&self) -> f64 {
+        self.summary.win_rate()
+    }
+
+    /// See [`Summary::average_guesses`]
+    pub fn average_guesses(&self) -> f64 {
+        self.summary.average_guesses()
+    }
+}
+
+/// Machine-readable formats of `wordle stats`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    #[value(name = "json")]
+    Json,
+    #[value(name = "csv")]
+    Csv,
+}
+
+/// One game of the history as exported
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GameRow {
+    /// 1-based position in the state file
+    pub number: usize,
+    /// Answers separated by spaces for multi-board games
+    pub answer: String,
+    pub guesses: Vec<String>,
+    pub guess_count: usize,
+    pub won: bool,
+    pub mode: String,
+    /// Date of daily puzzles, or the day other games started in UTC, YYYY-MM-DD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl GameRow {
+    /// Daily games use their saved date, `epoch` gives it from the day number for
+    /// games saved without one; other games use the date of `started_at`
+    pub fn new(number: usize, game: &Game, epoch: NaiveDate) -> Self {
+        GameRow {
+            number,
+            answer: match game.answers.is_empty() {
+                true => game.answer.clone(),
+                false => game.answers.join(" "),
+            },
+            guesses: game.guesses.clone(),
+            guess_count: game.guesses.len(),
+            won: game.is_won(),
+            mode: game.mode().to_string(),
+            date: game
+                .date
+                .as_deref()
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                .or_else(|| {
+                    let day = game.day?;
+                    epoch.checked_add_days(Days::new((day as u64).saturating_sub(1)))
+                })
+                .or_else(|| {
+                    let started_at = game.metadata.started_at.as_deref()?;
+                    DateTime::parse_from_rfc3339(started_at)
+                        .ok()
+                        .map(|time| time.with_timezone(&Utc).date_naive())
+                })
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportSummary<'a> {
+    #[serde(flatten)]
+    stats: &'a Stats,
+    losses: usize,
+    win_rate: f64,
+    average_guesses: f64,
+    /// Results grouped by the number of guesses allowed
+    by_max_guesses: BTreeMap<usize, AllowanceSummary>,
+    /// Seconds of the median timed game that was won
+    #[serde(skip_serializing_if = "Option::is_none")]
+    median_solve_time: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct AllowanceSummary {
+    wins: usize,
+    losses: usize,
+    average_guesses: f64,
+    win_rate: f64,
+}
+
+impl From<Summary> for AllowanceSummary {
+    fn from(summary: Summary) -> Self {
+        AllowanceSummary {
+            wins: summary.wins,
+            losses: summary.losses,
+            average_guesses: summary.average_guesses(),
+            win_rate: summary.win_rate(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+    games: &'a [GameRow],
+    summary: ExportSummary<'a>,
+}
+
+/// Rows of every game in the state
+pub fn game_rows(state: &State, epoch: NaiveDate) -> Vec<GameRow> {
+    state
+        .games
+        .iter()
+        .enumerate()
+        .map(|(i, game)| GameRow::new(i + 1, game, epoch))
+        .collect()
+}
+
+/// Renders the games and the aggregated figures in the given format
+pub fn export(state: &State, epoch: NaiveDate, format: ExportFormat) -> String {
+    let rows = game_rows(state, epoch);
+    let stats = Stats::from_games(&state.games);
+    match format {
+        ExportFormat::Json => {
+            let export = Export {
+                games: &rows,
+                summary: ExportSummary {
+                    stats: &stats,
+                    losses: stats.losses(),
+                    win_rate: stats.win_rate(),
+                    average_guesses: stats.average_guesses(),
+                    by_max_guesses: state
+                        .summary_by_max_guesses()
+                        .into_iter()
+                        .map(|(max_guesses, summary)| (max_guesses, summary.into()))
+                        .collect(),
+                    median_solve_time: state.median_solve_time(),
+                },
+            };
+            serde_json::to_string_pretty(&export).expect("statistics are serializable")
+        }
+        ExportFormat::Csv => export_csv(&rows, &stats, state),
+    }
+}
+
+/// The games as one table, then a blank line and the figures as `figure,value` rows
+fn export_csv(rows: &[GameRow], stats: &Stats, state: &State) -> String {
+    let mut csv = String::from("number,answer,guesses,guess_count,won,mode,date\n");
+    for row in rows {
+        let fields = [
+            row.number.to_string(),
+            row.answer.clone(),
+            row.guesses.join(" "),
+            row.guess_count.to_string(),
+            row.won.to_string(),
+            row.mode.clone(),
+            row.date.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    csv.push_str("\nfigure,value\n");
+    let mut figures = vec![
+        ("played".to_string(), stats.played.to_string()),
+        ("wins".to_string(), stats.wins.to_string()),
+        ("losses".to_string(), stats.losses().to_string()),
+        ("win_rate".to_string(), format!("{:.2}", stats.win_rate())),
+        (
+            "average_guesses".to_string(),
+            format!("{:.2}", stats.average_guesses()),
+        ),
+        (
+            "current_streak".to_string(),
+            stats.current_streak.to_string(),
+        ),
+        ("max_streak".to_string(), stats.max_streak.to_string()),
+    ];
+    if let Some(skill) = stats.skill {
+        figures.push(("skill".to_string(), format!("{:.2}", skill)));
+    }
+    for (i, count) in stats.distribution.iter().enumerate() {
+        figures.push((format!("won_in_{}", i + 1), count.to_string()));
+    }
+    if let Some(median) = state.median_solve_time() {
+        figures.push(("median_solve_time".to_string(), format!("{:.2}", median)));
+    }
+    for (max_guesses, summary) in state.summary_by_max_guesses() {
+        let prefix = format!("max_{}", max_guesses);
+        figures.push((format!("{}_wins", prefix), summary.wins.to_string()));
+        figures.push((format!("{}_losses", prefix), summary.losses.to_string()));
+        figures.push((
+            format!("{}_average_guesses", prefix),
+            format!("{:.2}", summary.average_guesses()),
+        ));
+        figures.push((
+            format!("{}_win_rate", prefix),
+            format!("{:.2}", summary.win_rate()),
+        ));
+    }
+    for (mode, count) in &stats.modes {
+        figures.push((format!("mode_{}", mode), count.to_string()));
+    }
+    for (figure, value) in figures {
+        csv.push_str(&format!("{},{}\n", csv_field(&figure), value));
+    }
+    csv
+}
+
+/// Quotes a field if it contains a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// The colour of a single tile or keyboard letter
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Status {
     UNKNOWN,
     RED,
@@ -6,28 +8,25 @@ pub enum Status {
     GREEN,
 }
 
-impl PartialOrd for Status {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        use std::cmp::Ordering;
-
-        match (self, other) {
-            (Status::GREEN, Status::GREEN)
-            | (Status::YELLOW, Status::YELLOW)
-            | (Status::RED, Status::RED)
-            | (Status::UNKNOWN, Status::UNKNOWN) => Some(Ordering::Equal),
-
-            (Status::GREEN, _)
-            | (Status::YELLOW, Status::RED)
-            | (Status::YELLOW, Status::UNKNOWN)
-            | (Status::RED, Status::UNKNOWN) => Some(Ordering::Greater),
-
-            _ => Some(Ordering::Less),
+impl Status {
+    /// Parses the letter used for a status in the non-interactive output
+    pub fn from_char(c: char) -> Option<Status> {
+        match c.to_ascii_uppercase() {
+            'R' => Some(Status::RED),
+            'Y' => Some(Status::YELLOW),
+            'G' => Some(Status::GREEN),
+            'X' => Some(Status::UNKNOWN),
+            _ => None,
         }
     }
-}
 
-impl Ord for Status {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+    /// Letter used for the status in the non-interactive output
+    pub fn to_char(self) -> char {
+        match self {
+            Status::RED => 'R',
+            Status::YELLOW => 'Y',
+            Status::GREEN => 'G',
+            Status::UNKNOWN => 'X',
+        }
     }
 }
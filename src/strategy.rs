@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+
+use crate::matrix::FeedbackSource;
+use crate::solver::{best_guess, bucket_sizes, entropy};
+
+/// Opener used by the fixed opener strategy unless another one is given
+pub const DEFAULT_OPENER: &str = "SALET";
+
+/// An automatic way of choosing the next guess
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    /// Chooses the next guess from `pool`, given the answers still consistent
+    /// with the clues and the number of guesses already made
+    fn choose(
+        &self,
+        turn: usize,
+        pool: &[&String],
+        candidates: &[&String],
+        source: &dyn FeedbackSource,
+    ) -> Option<String>;
+}
+
+/// Built-in strategies selectable from the command line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StrategyKind {
+    /// Maximise the expected information of each guess
+    #[value(name = "entropy")]
+    Entropy,
+    /// Minimise the largest group of answers a guess can leave
+    #[value(name = "minimax")]
+    Minimax,
+    /// Guess the candidate made of the most frequent letters
+    #[value(name = "frequency")]
+    Frequency,
+    /// Play a fixed opener, then the most informative candidate
+    #[value(name = "opener")]
+    Opener,
+}
+
+impl StrategyKind {
+    /// Creates the strategy; `opener` is only used by the fixed opener strategy
+    pub fn build(self, opener: Option<&str>) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Entropy => Box::new(EntropyStrategy),
+            StrategyKind::Minimax => Box::new(MinimaxStrategy),
+            StrategyKind::Frequency => Box::new(FrequencyStrategy),
+            StrategyKind::Opener => Box::new(OpenerStrategy {
+                opener: opener.unwrap_or(DEFAULT_OPENER).trim().to_uppercase(),
+            }),
+        }
+    }
+}
+
+/// Guesses one of the candidates directly once there are no more than two
+fn endgame(candidates: &[&String]) -> Option<Option<String>> {
+    match candidates.len() {
+        0..=2 => Some(candidates.first().map(|word| word.to_string())),
+        _ => None,
+    }
+}
+
+/// Maximises the expected information over the remaining answers
+pub struct EntropyStrategy;
+
+impl Strategy for EntropyStrategy {
+    fn name(&self) -> &str {
+        "entropy"
+    }
+
+    fn choose(
+        &self,
+        _turn: usize,
+        pool: &[&String],
+        candidates: &[&String],
+        source: &dyn FeedbackSource,
+    ) -> Option<String> {
+        if let Some(word) = endgame(candidates) {
+            return word;
+        }
+        best_guess(pool.iter().copied(), candidates, |word| {
+            entropy(word, candidates, source)
+        })
+        .map(|(word, _)| word.to_string())
+    }
+}
+
+/// Minimises the number of answers left in the worst case
+pub struct MinimaxStrategy;
+
+impl Strategy for MinimaxStrategy {
+    fn name(&self) -> &str {
+        "minimax"
+    }
+
+    fn choose(
+        &self,
+        _turn: usize,
+        pool: &[&String],
+        candidates: &[&String],
+        source: &dyn FeedbackSource,
+    ) -> Option<String> {
+        if let Some(word) = endgame(candidates) {
+            return word;
+        }
+        best_guess(pool.iter().copied(), candidates, |word| {
+            let largest = bucket_sizes(word, candidates, source)
+                .into_iter()
+                .max()
+                .unwrap_or(0);
+            -(largest as f64)
+        })
+        .map(|(word, _)| word.to_string())
+    }
+}
+
+/// Guesses the candidate whose distinct letters are the most common among the candidates
+pub struct FrequencyStrategy;
+
+impl Strategy for FrequencyStrategy {
+    fn name(&self) -> &str {
+        "frequency"
+    }
+
+    fn choose(
+        &self,
+        _turn: usize,
+        _pool: &[&String],
+        candidates: &[&String],
+        _source: &dyn FeedbackSource,
+    ) -> Option<String> {
+        let mut frequency: HashMap<char, usize> = HashMap::new();
+        for word in candidates {
+            for letter in word.chars() {
+                *frequency.entry(letter).or_insert(0) += 1;
+            }
+        }
+        best_guess(candidates.iter().copied(), candidates, |word| {
+            let mut letters: Vec<char> = word.chars().collect();
+            letters.sort_unstable();
+            letters.dedup();
+            letters
+                .iter()
+                .map(|letter| frequency[letter])
+                .sum::<usize>() as f64
+        })
+        .map(|(word, _)| word.to_string())
+    }
+}
+
+/// Plays a fixed opener, then greedily the candidate with the highest entropy
+pub struct OpenerStrategy {
+    pub opener: String,
+}
+
+impl Strategy for OpenerStrategy {
+    fn name(&self) -> &str {
+        "opener"
+    }
+
+    fn choose(
+        &self,
+        turn: usize,
+        _pool: &[&String],
+        candidates: &[&String],
+        source: &dyn FeedbackSource,
+    ) -> Option<String> {
+        if turn == 0 {
+            return Some(self.opener.clone());
+        }
+        if let Some(word) = endgame(candidates) {
+            return word;
+        }
+        best_guess(candidates.iter().copied(), candidates, |word| {
+            entropy(word, candidates, source)
+        })
+        .map(|(word, _)| word.to_string())
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Which time limit ran out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUp {
+    Game,
+    Guess,
+}
+
+impl fmt::Display for TimeUp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeUp::Game => write!(f, "time limit of the game exceeded"),
+            TimeUp::Guess => write!(f, "time limit of the guess exceeded"),
+        }
+    }
+}
+
+/// Measures the time taken by each guess of a game, with optional countdowns
+#[derive(Clone, Debug)]
+pub struct Timer {
+    started: Instant,
+    last: Instant,
+    game_limit: Option<Duration>,
+    guess_limit: Option<Duration>,
+    /// Seconds taken by each accepted guess
+    laps: Vec<f64>,
+}
+
+impl Timer {
+    /// Starts timing; limits are checked with [`Timer::check`]
+    pub fn new(game_limit: Option<Duration>, guess_limit: Option<Duration>) -> Self {
+        let now = Instant::now();
+        Timer {
+            started: now,
+            last: now,
+            game_limit,
+            guess_limit,
+            laps: Vec::new(),
+        }
+    }
+
+    /// Fails once the game or the current guess has used up the time allowed
+    pub fn check(&self) -> Result<(), TimeUp> {
+        let now = Instant::now();
+        if self
+            .game_limit
+            .is_some_and(|limit| now - self.started >= limit)
+        {
+            return Err(TimeUp::Game);
+        }
+        if self
+            .guess_limit
+            .is_some_and(|limit| now - self.last >= limit)
+        {
+            return Err(TimeUp::Guess);
+        }
+        Ok(())
+    }
+
+    /// Time left for the current guess under both limits, `None` without limits
+    pub fn remaining(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let game = self
+            .game_limit
+            .map(|limit| limit.saturating_sub(now - self.started));
+        let guess = self
+            .guess_limit
+            .map(|limit| limit.saturating_sub(now - self.last));
+        match (game, guess) {
+            (Some(game), Some(guess)) => Some(game.min(guess)),
+            (game, guess) => game.or(guess),
+        }
+    }
+
+    /// Records an accepted guess, returning the seconds it took to the millisecond
+    pub fn lap(&mut self) -> f64 {
+        let now = Instant::now();
+        // 保留到毫秒
+        let seconds = ((now - self.last).as_secs_f64() * 1000f64).round() / 1000f64;
+        self.last = now;
+        self.laps.push(seconds);
+        seconds
+    }
+
+    pub fn laps(&self) -> &[f64] {
+        &self.laps
+    }
+
+    /// Time since the game started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+use std::fs::read_to_string;
+
+use crate::alphabet::Alphabet;
+use crate::game::DEFAULT_WORD_LENGTH;
+
+/// Load word lists from files, rejecting words that are not `length` letters
+/// long or use letters outside the alphabet
+pub fn load_word_list(
+    file_path: &str,
+    length: usize,
+    alphabet: &Alphabet,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let content = read_to_string(file_path).map_err(|error| {
+        let mut message = format!("Cannot read the word list {}: {}", file_path, error);
+        // 只自带默认词长的词库
+        if length != DEFAULT_WORD_LENGTH {
+            message += &format!(
+                "; only lists of {} letters are bundled, give lists of {} letters with -f and -a",
+                DEFAULT_WORD_LENGTH, length
+            );
+        }
+        message
+    })?;
+    let word_list: Vec<String> = content
+        .lines()
+        .map(|line| alphabet.normalize(line))
+        .collect();
+
+    if let Some(word) = word_list.iter().find(|w| w.chars().count() != length) {
+        return Err(format!(
+            "Word \"{}\" in {} does not have {} letters",
+            word, file_path, length
+        )
+        .into());
+    }
+    if let Some(word) = word_list.iter().find(|w| !alphabet.contains_word(w)) {
+        return Err(format!(
+            "Word \"{}\" in {} has letters outside the alphabet",
+            word, file_path
+        )
+        .into());
+    }
+    let unique_words: HashSet<String> = word_list.iter().cloned().collect();
+
+    if unique_words.len() != word_list.len() {
+        return Err("Duplicate words found in the word list".into());
+    }
+
+    Ok(word_list)
+}
+
+/// Check subset
+pub fn check_subset(subset: &[String], superset: &[String]) -> bool {
+    let subset_set: HashSet<&String> = subset.iter().collect();
+    let superset_set: HashSet<&String> = superset.iter().collect();
+    subset_set.is_subset(&superset_set)
+}
+
+/// Default path of a bundled word list, e.g. `./final_set.txt` or `./final_set_6.txt`
+pub fn default_word_list_path(name: &str, length: usize) -> String {
+    if length == DEFAULT_WORD_LENGTH {
+        format!("./{}.txt", name)
+    } else {
+        format!("./{}_{}.txt", name, length)
+    }
+}
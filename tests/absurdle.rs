@@ -0,0 +1,47 @@
+mod fixtures;
+
+use fixtures::words;
+use wordle::absurdle::Adversary;
+use wordle::alphabet::Alphabet;
+use wordle::game::{Rules, Session};
+use wordle::status::Status::{RED, YELLOW};
+
+/// Candidates left after the adversary settles `guess` in a fresh session
+fn settle(final_set: &[String], acceptable_set: &[String], guess: &str) -> Vec<String> {
+    let alphabet = Alphabet::latin();
+    let mut adversary = Adversary::new(final_set).unwrap();
+    let mut session = Session::new(
+        adversary.answer(),
+        Rules::default(),
+        acceptable_set,
+        &alphabet,
+    )
+    .unwrap();
+    adversary.settle(&mut session, guess).unwrap();
+    assert_eq!(session.answer(), adversary.answer().as_str());
+    let feedback = session.guess(guess).unwrap();
+    assert_eq!(feedback.word_state, vec![RED, RED, RED, RED, YELLOW]);
+    adversary
+        .candidates()
+        .iter()
+        .map(|word| word.to_string())
+        .collect()
+}
+
+#[test]
+fn test_largest_group_is_kept() {
+    let final_set = words(&["CRYPT", "SLEPT", "SWEPT"]);
+    let acceptable_set = words(&["CRANE", "CRYPT", "SLEPT", "SWEPT"]);
+    assert_eq!(
+        settle(&final_set, &acceptable_set, "CRANE"),
+        vec!["SLEPT", "SWEPT"]
+    );
+}
+
+#[test]
+fn test_ties_go_to_fewer_revealed_tiles() {
+    // GGRRR against CRYPT and RRRRY against SLEPT, the single yellow tile wins
+    let final_set = words(&["CRYPT", "SLEPT"]);
+    let acceptable_set = words(&["CRANE", "CRYPT", "SLEPT"]);
+    assert_eq!(settle(&final_set, &acceptable_set, "CRANE"), vec!["SLEPT"]);
+}
@@ -0,0 +1,82 @@
+mod fixtures;
+
+use fixtures::words;
+use wordle::alphabet::Alphabet;
+use wordle::coach::{Coach, GameRating};
+use wordle::game::{Rules, Session};
+
+/// Word, candidates, expected, best word, best expected and remaining answers of each guess
+fn summarize(rating: &GameRating) -> Vec<(&str, usize, f64, &str, f64, usize)> {
+    rating
+        .guesses
+        .iter()
+        .map(|guess| {
+            (
+                guess.word.as_str(),
+                guess.candidates,
+                guess.expected,
+                guess.best_word.as_str(),
+                guess.best_expected,
+                guess.remaining,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_rate_small_game() {
+    let alphabet = Alphabet::latin();
+    let final_set = words(&["BAKER", "CRANE", "SLATE", "TRACE"]);
+    let acceptable_set = words(&["BAKER", "CRANE", "SLATE", "SPEED", "TRACE"]);
+    let mut session = Session::new("TRACE", Rules::default(), &acceptable_set, &alphabet).unwrap();
+    for word in ["SPEED", "CRANE", "TRACE"] {
+        session.guess(word).unwrap();
+    }
+
+    let coach = Coach::new(&final_set);
+    let rating = coach.rate(&session, &alphabet).unwrap();
+    assert_eq!(
+        summarize(&rating),
+        vec![
+            ("SPEED", 4, 1.5, "CRANE", 1.0, 2),
+            ("CRANE", 2, 1.0, "CRANE", 1.0, 1),
+            ("TRACE", 1, 1.0, "TRACE", 1.0, 1),
+        ]
+    );
+    assert_eq!(rating.skill(), 88.89);
+    assert_eq!(rating.luck(), -0.5);
+
+    // the best opening found for the first game is reused
+    let mut session = Session::new("BAKER", Rules::default(), &acceptable_set, &alphabet).unwrap();
+    for word in ["SLATE", "BAKER"] {
+        session.guess(word).unwrap();
+    }
+    let rating = coach.rate(&session, &alphabet).unwrap();
+    assert_eq!(
+        summarize(&rating),
+        vec![
+            ("SLATE", 4, 1.0, "CRANE", 1.0, 1),
+            ("BAKER", 1, 1.0, "BAKER", 1.0, 1),
+        ]
+    );
+    assert_eq!(rating.skill(), 100.0);
+    assert_eq!(rating.luck(), 0.0);
+}
+
+#[test]
+fn test_rate_needs_a_known_answer() {
+    let alphabet = Alphabet::latin();
+    let final_set = words(&["CRANE", "SLATE"]);
+    let acceptable_set = words(&["BAKER", "CRANE", "SLATE"]);
+    let coach = Coach::new(&final_set);
+
+    // no guesses
+    let session = Session::new("CRANE", Rules::default(), &acceptable_set, &alphabet).unwrap();
+    assert_eq!(coach.rate(&session, &alphabet), None);
+
+    // the clues rule out every answer of the final list
+    let mut session = Session::new("BAKER", Rules::default(), &acceptable_set, &alphabet).unwrap();
+    session.guess("SLATE").unwrap();
+    session.guess("BAKER").unwrap();
+    assert_eq!(coach.rate(&session, &alphabet), None);
+}
@@ -7,6 +7,7 @@ use std::process::{Child, Command, Stdio};
 use assert_json_diff::assert_json_eq;
 use lazy_static::lazy_static;
 use pretty_assertions::assert_eq;
+#[allow(clippy::single_component_path_imports)]
 use serde_json;
 
 // Binary targets are automatically built if there is an integration test.
@@ -100,8 +101,10 @@ impl TestCase {
         // read state before & end
         let case_dir = Path::new("tests").join("cases");
         let before_state_file = case_dir.join(format!("{}.before.json", self.name));
-        let run_state_file = case_dir.join(format!("{}.run.json", self.name));
         let after_state_file = case_dir.join(format!("{}.after.json", self.name));
+        // files written next to the state, e.g. backups of upgraded versions, stay out of the cases
+        let run_dir = tempfile::tempdir().unwrap();
+        let run_state_file = run_dir.path().join(format!("{}.run.json", self.name));
 
         // run with temporary state file
         std::fs::copy(&before_state_file, &run_state_file).unwrap();
@@ -112,14 +115,51 @@ impl TestCase {
         self.run_and_compare_result();
 
         // load state and compare with answer
-        let run_state: serde_json::Value =
+        let mut run_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&run_state_file).unwrap())).unwrap();
+        let before_state: serde_json::Value =
+            serde_json::from_reader(BufReader::new(File::open(&before_state_file).unwrap()))
+                .unwrap();
+        self.check_timestamps(&mut run_state, &before_state);
         let answer_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&after_state_file).unwrap()))
                 .unwrap();
         assert_json_eq!(run_state, answer_state);
     }
 
+    // the timestamps of games played in this run cannot be known in advance,
+    // so check that they are recorded in order and leave them out of the comparison
+    fn check_timestamps(
+        &self,
+        run_state: &mut serde_json::Value,
+        before_state: &serde_json::Value,
+    ) {
+        let played_before = before_state["games"]
+            .as_array()
+            .map_or(0, |games| games.len());
+        let games = match run_state["games"].as_array_mut() {
+            Some(games) => games,
+            None => return,
+        };
+        for game in games.iter_mut().skip(played_before) {
+            let game = game.as_object_mut().unwrap();
+            let times: Vec<_> = ["started_at", "ended_at"]
+                .iter()
+                .map(|key| {
+                    let time = game.remove(*key);
+                    let time = time.as_ref().and_then(|time| time.as_str());
+                    time.and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+                        .unwrap_or_else(|| panic!("case {} should record {}", self.name, key))
+                })
+                .collect();
+            assert!(
+                times[0] <= times[1],
+                "case {} should end games after they start",
+                self.name
+            );
+        }
+    }
+
     pub fn run_and_expect_exit(&self) {
         let command = self.execute_program_and_feed_input();
         assert!(
@@ -0,0 +1,236 @@
+use ntest::timeout;
+
+mod common;
+use common::TestCase;
+
+#[test]
+#[timeout(2000)]
+fn test_09_word_length() {
+    // six-letter words with a matching word list
+    TestCase::read("09_01_word_length").run_and_compare_result();
+    // word list entries that do not match the requested length
+    TestCase::read("09_02_invalid_word_length").run_and_expect_exit();
+    // no word lists of other lengths are bundled
+    TestCase::read("09_03_missing_word_list").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_10_max_guesses() {
+    // a shorter allowance is recorded in the state, the protocol lines stay unchanged
+    TestCase::read("10_01_max_guesses").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_11_alphabet() {
+    // Spanish alphabet loaded from a file
+    TestCase::read("11_01_alphabet").run_and_compare_result();
+    // word list with letters outside the default alphabet
+    TestCase::read("11_02_letters_outside_alphabet").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_12_difficult_mode_levels() {
+    // strict mode enforces the clues of every previous guess
+    TestCase::read("12_01_strict_difficult_mode").run_and_compare_result();
+    // ultra mode only accepts guesses that could still be the answer
+    TestCase::read("12_02_ultra_difficult_mode").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_13_hint() {
+    // hints before every guess and on the /hint command
+    TestCase::read("13_01_hint").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_14_bench() {
+    // benchmark a strategy over a small answer list
+    TestCase::read("14_01_bench").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_15_filter() {
+    // answers consistent with two clues
+    TestCase::read("15_01_filter").run_and_compare_result();
+    // pattern shorter than its guess
+    TestCase::read("15_02_invalid_clue").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_16_feedback_matrix() {
+    // the first run builds and saves the matrix, the second one loads it without writing again
+    let cache_dir = std::path::Path::new("target").join("test-cache");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    TestCase::read("16_01_feedback_matrix").run_and_compare_result();
+    let cached: Vec<_> = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(cached.len(), 1);
+    let modified = std::fs::metadata(&cached[0]).unwrap().modified().unwrap();
+    TestCase::read("16_01_feedback_matrix").run_and_compare_result();
+    assert_eq!(
+        std::fs::metadata(&cached[0]).unwrap().modified().unwrap(),
+        modified
+    );
+}
+
+#[test]
+#[timeout(2000)]
+fn test_17_multi_board() {
+    // two boards share guesses and a solved board reports SOLVED
+    TestCase::read("17_01_multi_board").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_18_absurdle() {
+    // the answer keeps dodging the guesses and is settled when the game ends
+    TestCase::read("18_01_absurdle").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_19_reverse() {
+    // a seeded puzzle solved row by row with one miss
+    TestCase::read("19_01_reverse").run_and_compare_result();
+    // a single guess leaves no row to play
+    TestCase::read("19_02_reverse_one_guess").run_and_expect_exit();
+    // a saved puzzle without rows does not break the statistics
+    TestCase::read("19_03_empty_reverse_stats").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_20_daily() {
+    // the day is counted from the epoch and recorded in the state
+    TestCase::read("20_01_daily").run_and_compare_game_state();
+    // the same profile cannot replay a finished day
+    TestCase::read("20_02_daily_replayed").run_and_expect_exit();
+    // days past the end of the word list wrap around
+    TestCase::read("20_03_day_wraps").run_and_compare_result();
+    // days are counted from 1
+    TestCase::read("20_04_day_zero").run_and_expect_exit();
+    // the largest day wraps around without overflowing
+    TestCase::read("20_05_huge_day").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_21_time_limit() {
+    // a zero limit is used up before any guess, whatever the timing of the input
+    TestCase::read("21_01_guess_time_limit").run_and_compare_result();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_22_fibble() {
+    // one tile of every row is wrong and hints follow the lie model
+    TestCase::read("22_01_fibble").run_and_compare_result();
+    // the filter accepts clues with one wrong tile
+    TestCase::read("22_02_filter_lies").run_and_compare_result();
+    // the seed of the lies is saved with the game
+    TestCase::read("22_03_fibble_state").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_23_preset_guesses() {
+    // preset guesses are replayed before the player continues
+    TestCase::read("23_01_preset_guesses").run_and_compare_result();
+    // preset guesses go through the usual validation
+    TestCase::read("23_02_invalid_preset").run_and_expect_exit();
+    // the last game of the state is resumed before its final guess
+    TestCase::read("23_03_resume").run_and_compare_game_state();
+    // the resumed game keeps the guesses it was allowed
+    TestCase::read("23_04_resume_saved_allowance").run_and_compare_game_state();
+    // and the length it was played with
+    TestCase::read("23_05_resume_saved_length").run_and_compare_game_state();
+    // a multi-board game keeps its allowance however often it is resumed
+    TestCase::read("23_06_resume_multi_board_allowance").run_and_compare_game_state();
+    // reverse puzzles cannot be resumed as word games
+    TestCase::read("23_07_resume_reverse").run_and_expect_exit();
+    // nor Fibble games
+    TestCase::read("23_08_resume_fibble").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_24_replay() {
+    // a saved game row by row with the candidates left
+    TestCase::read("24_01_replay").run_and_compare_result();
+    // every board of a multi-board game
+    TestCase::read("24_02_replay_multi_board").run_and_compare_result();
+    // a game number past the end of the state
+    TestCase::read("24_03_replay_missing_game").run_and_expect_exit();
+    // a reverse puzzle with its target rows and the words that matched them
+    TestCase::read("24_04_replay_reverse").run_and_compare_result();
+    // a game of another length replays without the candidate word list
+    TestCase::read("24_05_replay_other_length").run_and_compare_result();
+    // but its candidates cannot be counted with the default word list
+    TestCase::read("24_06_replay_other_length_candidates").run_and_expect_exit();
+    // answers with letters outside the alphabet or of another length
+    TestCase::read("24_07_replay_foreign_answer").run_and_expect_exit();
+    TestCase::read("24_08_replay_long_answer").run_and_expect_exit();
+    // a Fibble game with the lies the player saw
+    TestCase::read("24_09_replay_fibble").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_25_stats_export() {
+    // games and figures as JSON, with the date of daily games
+    TestCase::read("25_01_stats_json").run_and_compare_result();
+    // games and figures as CSV, broken down by the guesses allowed
+    TestCase::read("25_02_stats_csv").run_and_compare_result();
+    // the export only reads the state file, not the word lists
+    TestCase::read("25_03_stats_without_word_lists").run_and_compare_result();
+    // daily games keep the date they were played for whatever the epoch
+    TestCase::read("25_04_stats_saved_daily_date").run_and_compare_result();
+    // older daily games without a date count their day from the epoch
+    TestCase::read("25_05_stats_older_daily_date").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_26_analyze() {
+    // openers, positions, absent letters and hardest answers as tables
+    TestCase::read("26_01_analyze_table").run_and_compare_result();
+    // the same analysis as JSON with a shorter top list
+    TestCase::read("26_02_analyze_json").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_27_skill_rating() {
+    // average skill of the rated games in the exported summary
+    TestCase::read("27_01_stats_skill").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_28_game_metadata() {
+    // games with metadata load next to old ones, absurdle games get their own mode
+    // and are dated by their start
+    TestCase::read("28_01_metadata_stats").run_and_compare_result();
+    // metadata of earlier games survives saving a new game, including the old hard-mode flag
+    TestCase::read("28_02_metadata_kept").run_and_compare_game_state();
+    // every new game records its mode, difficult level, seed and word lists
+    TestCase::read("28_03_metadata_recorded").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_29_state_version() {
+    // a state file written by a newer version is not opened
+    TestCase::read("29_01_newer_version").run_and_expect_exit();
+    // a file of the current version loads as it is
+    TestCase::read("29_02_versioned_stats").run_and_compare_result();
+}
@@ -0,0 +1,157 @@
+use std::fs;
+
+mod fixtures;
+
+use fixtures::words;
+use ntest::timeout;
+use wordle::alphabet::Alphabet;
+use wordle::matrix::{
+    cache_path, decode, encode, FeedbackMatrix, FeedbackSource, MAX_MATRIX_LENGTH,
+};
+use wordle::solver::feedback;
+use wordle::status::Status;
+use wordle::words::load_word_list;
+
+/// Every pattern of the matrix, row by row
+fn patterns(matrix: &FeedbackMatrix, guesses: &[String], answers: &[String]) -> Vec<u32> {
+    (0..guesses.len())
+        .flat_map(|guess| (0..answers.len()).map(move |answer| matrix.get(guess, answer)))
+        .collect()
+}
+
+#[test]
+fn test_encode_decode_round_trip() {
+    // the first letter is the lowest digit
+    assert_eq!(
+        encode(&[
+            Status::GREEN,
+            Status::YELLOW,
+            Status::RED,
+            Status::RED,
+            Status::RED
+        ]),
+        2 + 3
+    );
+    assert_eq!(encode(&[Status::UNKNOWN, Status::GREEN]), 6);
+    for length in 1..=MAX_MATRIX_LENGTH {
+        for pattern in 0..3u32.pow(length as u32) {
+            let word_state = decode(pattern, length);
+            assert_eq!(word_state.len(), length);
+            assert_eq!(encode(&word_state), pattern);
+        }
+    }
+}
+
+/// The counting rules of the original `update_state`, kept as the reference of the scorer
+fn reference_pattern(guess: &[u8], answer: &[u8]) -> u32 {
+    let mut word_state = [0; MAX_MATRIX_LENGTH];
+    let mut counted = [0; 26];
+    let mut std_count = [0; 26];
+    for &letter in answer {
+        std_count[(letter - b'A') as usize] += 1;
+    }
+    for (i, (&guess_letter, &std_letter)) in guess.iter().zip(answer).enumerate() {
+        if guess_letter == std_letter {
+            counted[(guess_letter - b'A') as usize] += 1;
+            word_state[i] = 2;
+        }
+    }
+    for (i, (&guess_letter, &std_letter)) in guess.iter().zip(answer).enumerate() {
+        let index = (guess_letter - b'A') as usize;
+        if guess_letter != std_letter {
+            counted[index] += 1;
+            if counted[index] <= std_count[index] {
+                word_state[i] = 1;
+            }
+        }
+    }
+    word_state
+        .iter()
+        .rev()
+        .fold(0, |acc, digit| acc * 3 + digit)
+}
+
+#[test]
+#[timeout(60000)]
+fn test_patterns_match_update_state_on_bundled_lists() {
+    let alphabet = Alphabet::latin();
+    let guesses = load_word_list("acceptable_set.txt", 5, &alphabet).unwrap();
+    let answers = load_word_list("final_set.txt", 5, &alphabet).unwrap();
+    let matrix = FeedbackMatrix::build(&guesses, &answers, &alphabet).unwrap();
+    for (i, guess) in guesses.iter().enumerate() {
+        for (j, answer) in answers.iter().enumerate() {
+            assert_eq!(
+                matrix.get(i, j),
+                reference_pattern(guess.as_bytes(), answer.as_bytes()),
+                "{} against {}",
+                guess,
+                answer
+            );
+        }
+    }
+    // update_state colours the tiles the same way
+    for (guess, answer) in guesses.iter().zip(answers.iter().cycle()) {
+        assert_eq!(
+            encode(&feedback(guess, answer, &alphabet)),
+            alphabet.pattern(guess, answer)
+        );
+    }
+}
+
+#[test]
+fn test_save_and_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let alphabet = Alphabet::latin();
+    let guesses = words(&["CRANE", "EERIE", "SLATE", "SPEED"]);
+    let answers = words(&["ENTER", "HEDGE", "TRACE"]);
+    let path = cache_path(dir.path(), &guesses, &answers);
+
+    let built = FeedbackMatrix::build(&guesses, &answers, &alphabet).unwrap();
+    built.save(&path, &guesses, &answers).unwrap();
+    let loaded = FeedbackMatrix::load(&path, &guesses, &answers, &alphabet).unwrap();
+    assert_eq!(
+        patterns(&loaded, &guesses, &answers),
+        patterns(&built, &guesses, &answers)
+    );
+
+    // the same words in another order give other rows
+    let error = FeedbackMatrix::load(&path, &answers, &guesses, &alphabet).unwrap_err();
+    assert!(error.to_string().contains("was built for other word lists"));
+    let mut reordered = guesses.clone();
+    reordered.swap(0, 1);
+    let error = FeedbackMatrix::load(&path, &reordered, &answers, &alphabet).unwrap_err();
+    assert!(error.to_string().contains("was built for other word lists"));
+
+    let other = dir.path().join("other.bin");
+    fs::write(&other, "CRANE\nSLATE\n").unwrap();
+    let error = FeedbackMatrix::load(&other, &guesses, &answers, &alphabet).unwrap_err();
+    assert!(error.to_string().contains("is not a feedback matrix"));
+}
+
+#[test]
+fn test_load_or_build_uses_the_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let alphabet = Alphabet::latin();
+    let guesses = words(&["CRANE", "SLATE"]);
+    let answers = words(&["TRACE"]);
+    let path = cache_path(dir.path(), &guesses, &answers);
+
+    let built = FeedbackMatrix::load_or_build(dir.path(), &guesses, &answers, &alphabet).unwrap();
+    assert!(path.exists());
+
+    // 改动缓存里的一格，能读出改动说明矩阵是从文件载入的
+    let mut content = fs::read(&path).unwrap();
+    let last = content.len() - 1;
+    content[last] = 0;
+    fs::write(&path, &content).unwrap();
+    let loaded = FeedbackMatrix::load_or_build(dir.path(), &guesses, &answers, &alphabet).unwrap();
+    assert_ne!(built.get(1, 0), 0);
+    assert_eq!(loaded.get(1, 0), 0);
+
+    // a cache that does not fit the lists is rebuilt and overwritten
+    content.truncate(last);
+    fs::write(&path, &content).unwrap();
+    let rebuilt = FeedbackMatrix::load_or_build(dir.path(), &guesses, &answers, &alphabet).unwrap();
+    assert_eq!(rebuilt.get(1, 0), built.get(1, 0));
+    assert!(FeedbackMatrix::load(&path, &guesses, &answers, &alphabet).is_ok());
+}
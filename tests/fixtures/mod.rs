@@ -0,0 +1,6 @@
+//! Helpers shared by the library tests
+
+/// Owned word list from string literals
+pub fn words(list: &[&str]) -> Vec<String> {
+    list.iter().map(|word| word.to_string()).collect()
+}
@@ -0,0 +1,107 @@
+mod fixtures;
+
+use fixtures::words;
+use wordle::alphabet::Alphabet;
+use wordle::game::{Rules, Session};
+use wordle::hard_mode::HardMode;
+
+const WORDS: &[&str] = &[
+    "CAMEO", "CRANE", "EERIE", "ENTER", "ETHER", "HEDGE", "NEVER", "SHORE", "SPEED", "THERE",
+    "TREND", "WHOSE",
+];
+
+/// Plays every guess but the last one, then returns the message of the last guess
+fn last_guess(mode: HardMode, answer: &str, guesses: &[&str]) -> Result<(), String> {
+    let alphabet = Alphabet::latin();
+    let acceptable_set = words(WORDS);
+    let rules = Rules {
+        difficult: Some(mode),
+        ..Rules::default()
+    };
+    let mut session = Session::new(answer, rules, &acceptable_set, &alphabet).unwrap();
+    let (last, played) = guesses.split_last().unwrap();
+    for guess in played {
+        session.guess(guess).unwrap();
+    }
+    session
+        .guess(last)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+#[test]
+fn test_nyt_mode() {
+    // SHORE against THERE leaves H, R and E green
+    assert_eq!(
+        last_guess(HardMode::NYT, "THERE", &["SHORE", "WHOSE"]),
+        Err("4th letter must be R".to_string())
+    );
+    // HEDGE against ENTER reveals two yellow E
+    assert_eq!(
+        last_guess(HardMode::NYT, "ENTER", &["HEDGE", "TREND"]),
+        Err("guess must contain 2 × E".to_string())
+    );
+    // yellows may stay where they were revealed
+    assert_eq!(
+        last_guess(HardMode::NYT, "ENTER", &["HEDGE", "NEVER"]),
+        Ok(())
+    );
+    // and red letters may be played again
+    assert_eq!(
+        last_guess(HardMode::NYT, "ENTER", &["HEDGE", "THERE"]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_strict_mode() {
+    assert_eq!(
+        last_guess(HardMode::STRICT, "THERE", &["SHORE", "WHOSE"]),
+        Err("4th letter must be R".to_string())
+    );
+    assert_eq!(
+        last_guess(HardMode::STRICT, "ENTER", &["HEDGE", "TREND"]),
+        Err("guess must contain 2 × E".to_string())
+    );
+    // a yellow letter cannot stay where it was revealed
+    assert_eq!(
+        last_guess(HardMode::STRICT, "ENTER", &["HEDGE", "NEVER"]),
+        Err("2nd letter cannot be E".to_string())
+    );
+    // H was red in HEDGE
+    assert_eq!(
+        last_guess(HardMode::STRICT, "ENTER", &["HEDGE", "ETHER"]),
+        Err("guess must not contain H".to_string())
+    );
+    // extra copies of a present letter and red positions are allowed
+    assert_eq!(
+        last_guess(HardMode::STRICT, "CRANE", &["SPEED", "EERIE"]),
+        Ok(())
+    );
+    assert_eq!(
+        last_guess(HardMode::STRICT, "CRANE", &["SPEED", "CAMEO"]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_ultra_mode() {
+    assert_eq!(
+        last_guess(HardMode::ULTRA, "ENTER", &["HEDGE", "NEVER"]),
+        Err("2nd letter cannot be E".to_string())
+    );
+    // SPEED against CRANE has one yellow E and one red E, so CRANE has a single E
+    assert_eq!(
+        last_guess(HardMode::ULTRA, "CRANE", &["SPEED", "EERIE"]),
+        Err("guess must contain at most 1 × E".to_string())
+    );
+    // the red E rules out its position
+    assert_eq!(
+        last_guess(HardMode::ULTRA, "CRANE", &["SPEED", "CAMEO"]),
+        Err("4th letter cannot be E".to_string())
+    );
+    assert_eq!(
+        last_guess(HardMode::ULTRA, "CRANE", &["SPEED", "CRANE"]),
+        Ok(())
+    );
+}
@@ -0,0 +1,27 @@
+mod fixtures;
+
+use fixtures::words;
+use wordle::{Alphabet, AnswerError, MultiSession, Rules, Session};
+
+#[test]
+fn test_answer_outside_alphabet() {
+    let alphabet = Alphabet::latin();
+    let acceptable_set = words(&["BISON", "CRANE"]);
+
+    let error = Session::new("cañon", Rules::default(), &acceptable_set, &alphabet).unwrap_err();
+    assert_eq!(error, AnswerError::OutsideAlphabet("CAÑON".to_string()));
+    assert_eq!(
+        error.to_string(),
+        "answer CAÑON has letters outside the alphabet"
+    );
+
+    // every board is checked
+    let answers = words(&["BISON", "CAÑON"]);
+    let error =
+        MultiSession::new(&answers, Rules::default(), &acceptable_set, &alphabet).unwrap_err();
+    assert_eq!(error, AnswerError::OutsideAlphabet("CAÑON".to_string()));
+
+    let mut session = Session::new("bison", Rules::default(), &acceptable_set, &alphabet).unwrap();
+    assert_eq!(session.answer(), "BISON");
+    assert!(session.guess("crane").is_ok());
+}
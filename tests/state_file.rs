@@ -0,0 +1,108 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use ntest::timeout;
+use wordle::state::{backup_path, State, StateFile, STATE_VERSION};
+
+/// Names of the files left in a directory
+fn files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_lock_is_exclusive_and_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    let (mut file, mut state) = StateFile::open(&path).unwrap();
+    assert_eq!(files(dir.path()), vec!["state.json.lock"]);
+    let error = StateFile::open(&path).unwrap_err();
+    assert!(error.to_string().contains("used by another wordle process"));
+
+    state.total_rounds = 1;
+    file.save(&state).unwrap();
+    assert_eq!(files(dir.path()), vec!["state.json", "state.json.lock"]);
+    drop(file);
+    assert_eq!(files(dir.path()), vec!["state.json"]);
+
+    // the lock can be taken again once released
+    let (_, state) = StateFile::open(&path).unwrap();
+    assert_eq!(state.total_rounds, 1);
+}
+
+#[test]
+#[timeout(10000)]
+fn test_saves_after_each_game_under_lock() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["-r", "--state", path.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"crane\nclout\n").unwrap();
+
+    // the first game is saved while the player is asked for another one
+    let state = loop {
+        if let Ok(content) = fs::read(&path) {
+            break State::from_json(&content).unwrap().0;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    assert_eq!(state.total_rounds, 1);
+    assert_eq!(state.games[0].guesses, vec!["CRANE", "CLOUT"]);
+
+    // another process cannot open the same state meanwhile
+    let other = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["-w", "slate", "--state", path.to_str().unwrap()])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!other.status.success());
+    assert!(String::from_utf8_lossy(&other.stderr).contains("used by another wordle process"));
+
+    stdin.write_all(b"N\n").unwrap();
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    assert_eq!(files(dir.path()), vec!["state.json"]);
+}
+
+#[test]
+fn test_upgrade_keeps_a_backup_of_the_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    let original = r#"{"games": [{"answer": "CRANE", "guesses": ["CRANE"]}]}"#;
+    fs::write(&path, original).unwrap();
+
+    // opening alone leaves the file as it is
+    let (mut file, state) = StateFile::open(&path).unwrap();
+    assert_eq!(state.total_rounds, 1);
+    assert_eq!(files(dir.path()), vec!["state.json", "state.json.lock"]);
+
+    file.save(&state).unwrap();
+    drop(file);
+    assert_eq!(files(dir.path()), vec!["state.json", "state.json.v1.bak"]);
+    assert_eq!(fs::read_to_string(backup_path(&path, 1)).unwrap(), original);
+    let (upgraded, version) = State::from_json(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(version, STATE_VERSION);
+    assert_eq!(upgraded.total_rounds, 1);
+    assert_eq!(upgraded.games[0].guesses, vec!["CRANE"]);
+
+    // a file of the current version is not backed up again
+    fs::remove_file(backup_path(&path, 1)).unwrap();
+    let (mut file, state) = StateFile::open(&path).unwrap();
+    file.save(&state).unwrap();
+    drop(file);
+    assert_eq!(files(dir.path()), vec!["state.json"]);
+}
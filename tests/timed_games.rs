@@ -0,0 +1,133 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use ntest::timeout;
+use wordle::state::State;
+use wordle::timer::{TimeUp, Timer};
+
+/// Runs the binary on the one-answer word lists with `input` on standard input
+fn run(arguments: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["-f", "tests/data/timed_final.txt"])
+        .args(["-a", "tests/data/timed_acceptable.txt"])
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to execute process");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn lines(output: &Output) -> Vec<String> {
+    String::from_utf8(output.stdout.clone())
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn test_zero_limits_are_always_exceeded() {
+    let timer = Timer::new(Some(Duration::ZERO), None);
+    assert_eq!(timer.check(), Err(TimeUp::Game));
+    assert_eq!(timer.remaining(), Some(Duration::ZERO));
+
+    let timer = Timer::new(Some(Duration::from_secs(3600)), Some(Duration::ZERO));
+    assert_eq!(timer.check(), Err(TimeUp::Guess));
+    assert_eq!(timer.remaining(), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_remaining_takes_the_shorter_limit() {
+    let timer = Timer::new(
+        Some(Duration::from_secs(3600)),
+        Some(Duration::from_secs(60)),
+    );
+    assert_eq!(timer.check(), Ok(()));
+    let remaining = timer.remaining().unwrap();
+    assert!(remaining <= Duration::from_secs(60) && remaining > Duration::from_secs(50));
+    assert_eq!(Timer::new(None, None).remaining(), None);
+}
+
+#[test]
+#[timeout(4000)]
+fn test_game_time_limit() {
+    // the whole game has no time at all
+    let output = run(&["-w", "crane", "--time-limit", "0"], "CRANE\n");
+    assert!(output.status.success());
+    assert_eq!(lines(&output), ["TIMEOUT", "FAILED CRANE"]);
+}
+
+#[test]
+#[timeout(6000)]
+fn test_time_limit_while_waiting_for_input() {
+    // standard input stays open without a guess, the limit still ends the game
+    let started = Instant::now();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["-f", "tests/data/timed_final.txt"])
+        .args(["-a", "tests/data/timed_acceptable.txt"])
+        .args(["-w", "crane", "--guess-time-limit", "1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to execute process");
+    let stdin = child.stdin.take().unwrap();
+    let output = child.wait_with_output().unwrap();
+    drop(stdin);
+    assert!(output.status.success());
+    assert_eq!(lines(&output), ["TIMEOUT", "FAILED CRANE"]);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[test]
+#[timeout(6000)]
+fn test_speedrun_saves_times() {
+    let directory = tempfile::tempdir().unwrap();
+    let state_path = directory.path().join("state.json");
+    let state_path = state_path.to_str().unwrap();
+    let output = run(
+        &["--speedrun", "2", "-S", state_path],
+        "SLATE\nCRANE\nCRANE\n",
+    );
+    assert!(output.status.success());
+
+    // 两局结束后报告总用时
+    let lines = lines(&output);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(
+        lines[..5],
+        [
+            "RRGRG GXXXGXXXXXXRXXXXXXRRXXXXXX",
+            "GGGGG GXGXGXXXXXXRXGXXXGRRXXXXXX",
+            "CORRECT 2",
+            "GGGGG GXGXGXXXXXXXXGXXXGXXXXXXXX",
+            "CORRECT 1",
+        ]
+    );
+    let fields: Vec<&str> = lines[5].split(' ').collect();
+    assert_eq!(fields[..3], ["SPEEDRUN", "2", "2"]);
+    assert!(fields[3].parse::<f64>().unwrap() < 4f64);
+
+    // 每一步的用时都记录在存档中
+    let (state, _) = State::from_json(&std::fs::read(state_path).unwrap()).unwrap();
+    let times: Vec<usize> = state.games.iter().map(|game| game.times.len()).collect();
+    assert_eq!(times, [2, 1]);
+    assert!(state.games.iter().all(|game| game.elapsed().is_some()));
+    let median = state.median_solve_time().unwrap();
+    assert!((0f64..4f64).contains(&median));
+
+    // 导出中带有中位用时
+    let output = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["stats", "--format", "csv", "-S", state_path])
+        .output()
+        .unwrap();
+    let export = String::from_utf8(output.stdout).unwrap();
+    assert!(export.contains(&format!("median_solve_time,{:.2}\n", median)));
+}
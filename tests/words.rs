@@ -0,0 +1,20 @@
+use wordle::alphabet::Alphabet;
+use wordle::words::{default_word_list_path, load_word_list};
+
+#[test]
+fn test_missing_word_list_names_the_file() {
+    let alphabet = Alphabet::latin();
+    let error = load_word_list("missing.txt", 5, &alphabet).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("Cannot read the word list missing.txt: "));
+    assert!(!error.to_string().contains("bundled"));
+
+    // lists of other lengths are not shipped
+    let path = default_word_list_path("final_set", 6);
+    let error = load_word_list(&path, 6, &alphabet).unwrap_err();
+    assert!(error.to_string().contains("./final_set_6.txt"));
+    assert!(error
+        .to_string()
+        .ends_with("give lists of 6 letters with -f and -a"));
+}